@@ -1,9 +1,12 @@
 use std::fs;
 
+use crate::logic::abilities::AbilityCurve;
+use crate::logic::goap::DecisionMaking;
+use crate::logic::mortality::MortalityModel;
+use crate::logic::vision::VisionShape;
 use crate::logic::GeometryType;
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
 
 pub const CONFIG_PATH: &str = "./data/config.json";
 
@@ -30,18 +33,37 @@ pub struct GameConfig {
     pub growth: ConfigValue<f32>,
     pub hunger_increase: ConfigValue<f32>,
     pub hunger_decrease: ConfigValue<f32>,
+    pub starvation_damage: ConfigValue<f32>,
+    pub health_recovery: ConfigValue<f32>,
+    pub weakness_threshold: ConfigValue<f32>,
     pub starting_people: ConfigValue<u32>,
     pub max_person_age: ConfigValue<u32>,
+    pub mortality_model: ConfigValue<MortalityModel>,
+    pub makeham_background: ConfigValue<f32>,
+    pub gompertz_initial: ConfigValue<f32>,
+    pub gompertz_growth: ConfigValue<f32>,
+    pub infant_mortality: ConfigValue<f32>,
+    pub life_table: ConfigValue<Vec<f32>>,
     pub person_ttl: ConfigValue<u32>,
     pub food_for_baby: ConfigValue<u32>,
+    pub conception_probability: ConfigValue<f32>,
+    pub gestation_length: ConfigValue<u32>,
+    pub pregnancy_hunger_increase: ConfigValue<f32>,
+    pub birth_cooldown: ConfigValue<u32>,
+    pub twins_probability: ConfigValue<f32>,
+    pub child_food_reserve: ConfigValue<u32>,
+    pub guardian_distance: ConfigValue<u32>,
     pub year_length: ConfigValue<u32>,
     pub growing_season_length: ConfigValue<f32>,
     pub death_lottery: ConfigValue<bool>,
     pub trade_allowed: ConfigValue<bool>,
-    pub min_fertile_age_male: ConfigValue<f32>,
-    pub max_fertile_age_male: ConfigValue<f32>,
-    pub min_fertile_age_female: ConfigValue<f32>,
-    pub max_fertile_age_female: ConfigValue<f32>,
+    pub min_fertile_age_male: ConfigValue<u32>,
+    pub max_fertile_age_male: ConfigValue<u32>,
+    pub min_fertile_age_female: ConfigValue<u32>,
+    pub max_fertile_age_female: ConfigValue<u32>,
+    pub initial_trait_variation: ConfigValue<f32>,
+    pub mutation_probability: ConfigValue<f32>,
+    pub mutation_strength: ConfigValue<f32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Component)]
@@ -58,12 +80,79 @@ pub struct AiConfig {
     pub food_amount_goal: ConfigValue<u32>,
     pub food_amount_threshold: ConfigValue<f32>,
     pub vision_range: ConfigValue<u32>,
+    pub vision_shape: ConfigValue<VisionShape>,
+    pub line_of_sight: ConfigValue<bool>,
+    pub occupied_tile_cost: ConfigValue<u32>,
+    pub max_path_search: ConfigValue<u32>,
+    pub trade_need_ratio: ConfigValue<f32>,
+    pub season_horizon: ConfigValue<u32>,
+    pub stockpile_factor: ConfigValue<f32>,
+    pub thinker: ConfigValue<String>,
+    pub decision_making: ConfigValue<DecisionMaking>,
+    pub personalities: ConfigValue<bool>,
+    pub max_plan_length: ConfigValue<u32>,
+    pub learning_rate: ConfigValue<f32>,
+    pub discount: ConfigValue<f32>,
+    pub exploration: ConfigValue<f32>,
+    pub training_episodes: ConfigValue<u32>,
+    pub episode_length: ConfigValue<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Component)]
+pub struct AbilitiesConfig {
+    pub movement_speed: ConfigValue<AbilityCurve>,
+    pub vision_range: ConfigValue<AbilityCurve>,
+    pub foraging_yield: ConfigValue<AbilityCurve>,
+    pub trading_skill: ConfigValue<AbilityCurve>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Component)]
+pub struct SocialConfig {
+    pub max_partners: ConfigValue<u32>,
+    pub choosiness: ConfigValue<f32>,
+    pub wealth_weight: ConfigValue<f32>,
+    pub age_weight: ConfigValue<f32>,
+    pub traits_weight: ConfigValue<f32>,
+    pub trust_weight: ConfigValue<f32>,
+    pub household_sharing: ConfigValue<f32>,
+    pub reproduce_only_with_partner: ConfigValue<bool>,
+    pub trust_per_interaction: ConfigValue<f32>,
+    pub trust_per_trade: ConfigValue<f32>,
+    pub trust_decay: ConfigValue<f32>,
+    pub gossip_share_count: ConfigValue<u32>,
+    pub gossip_reliability: ConfigValue<f32>,
+    pub min_reliability: ConfigValue<f32>,
+    pub min_confidence: ConfigValue<f32>,
+    pub knowledge_capacity: ConfigValue<u32>,
+    pub memory_strength: ConfigValue<f32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Component)]
+pub struct DiseaseConfig {
+    pub outbreak_probability: ConfigValue<f32>,
+    pub transmission_probability: ConfigValue<f32>,
+    pub incubation_period: ConfigValue<u32>,
+    pub infectious_period: ConfigValue<u32>,
+    pub sickness_hunger_increase: ConfigValue<f32>,
+    pub sickness_mortality: ConfigValue<f32>,
+    pub immunity_duration: ConfigValue<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Component)]
+pub struct TribesConfig {
+    pub join_trust: ConfigValue<f32>,
+    pub kinship_trust: ConfigValue<f32>,
+    pub sharing: ConfigValue<f32>,
+    pub territory_claim: ConfigValue<f32>,
+    pub territory_decay: ConfigValue<f32>,
+    pub exclusion_threshold: ConfigValue<f32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Component)]
 pub struct UiConfig {
     pub plot_time_range: ConfigValue<usize>,
     pub age_histogram_bins: ConfigValue<u32>,
+    pub show_territories: ConfigValue<bool>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Resource)]
@@ -72,14 +161,13 @@ pub struct Config {
     pub game: GameConfig,
     pub map: MapConfig,
     pub ai: AiConfig,
+    pub abilities: AbilitiesConfig,
+    pub social: SocialConfig,
+    pub disease: DiseaseConfig,
+    pub tribes: TribesConfig,
     pub ui: UiConfig,
 }
 
-#[derive(Component)]
-pub struct GenericConfig {
-    pub value: Value,
-}
-
 pub struct ConfigPlugin;
 
 impl Plugin for ConfigPlugin {
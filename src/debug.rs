@@ -11,7 +11,7 @@ pub struct DebugPlugin;
 
 impl Plugin for DebugPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugin(FrameTimeDiagnosticsPlugin::default())
+        app.add_plugin(FrameTimeDiagnosticsPlugin)
             // .add_plugin(DebugLinesPlugin::default())
             .add_system(ui::debug_window);
         // .add_system(ui::debug_lines);
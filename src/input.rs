@@ -1,4 +1,5 @@
 mod map;
+pub mod selection;
 
 use bevy::prelude::{App, Plugin};
 
@@ -8,6 +9,8 @@ pub struct InputPlugin;
 
 impl Plugin for InputPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system(input::map::movement);
+        app.init_resource::<selection::SelectedPerson>()
+            .add_system(input::map::movement)
+            .add_system(selection::select_person);
     }
 }
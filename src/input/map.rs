@@ -59,13 +59,7 @@ pub fn movement(
             ortho.scale -= config.camera.zoom_speed.value;
         }
 
-        if ortho.scale < MIN_ZOOM {
-            ortho.scale = MIN_ZOOM;
-        }
-
-        if ortho.scale > MAX_ZOOM {
-            ortho.scale = MAX_ZOOM;
-        }
+        ortho.scale = ortho.scale.clamp(MIN_ZOOM, MAX_ZOOM);
 
         let z = transform.translation.z;
         transform.translation +=
@@ -0,0 +1,71 @@
+use bevy::{prelude::*, render::camera::Camera, window::PrimaryWindow};
+use bevy_egui::EguiContexts;
+
+use crate::config::Config;
+use crate::logic::components::{Lookup, Person};
+use crate::logic::RealCoords;
+use crate::rendering::tiles::TILE_SIZE;
+
+/// Person picked on the map with the left mouse button, kept even after their death
+#[derive(Resource, Default)]
+pub struct SelectedPerson(pub Option<Entity>);
+
+pub fn select_person(
+    mut egui_context: EguiContexts,
+    mouse_input: Res<Input<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    people: Res<Lookup<Person>>,
+    config: Res<Config>,
+    mut selected: ResMut<SelectedPerson>,
+) {
+    if !mouse_input.just_pressed(MouseButton::Left) || egui_context.ctx_mut().is_pointer_over_area()
+    {
+        return;
+    }
+    if let (Ok(window), Ok((camera, camera_transform))) =
+        (windows.get_single(), cameras.get_single())
+    {
+        select_at_cursor(
+            window,
+            camera,
+            camera_transform,
+            &people,
+            &config,
+            &mut selected,
+        );
+    }
+}
+
+fn select_at_cursor(
+    window: &Window,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    people: &Res<Lookup<Person>>,
+    config: &Res<Config>,
+    selected: &mut ResMut<SelectedPerson>,
+) {
+    if let Some(world_position) = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor))
+        .map(|ray| ray.origin.truncate())
+    {
+        let x = (world_position.x / TILE_SIZE).round();
+        let y = (world_position.y / TILE_SIZE).round();
+        if x < 0.0
+            || y < 0.0
+            || x >= config.map.size_x.value as f32
+            || y >= config.map.size_y.value as f32
+        {
+            return;
+        }
+        let coords = RealCoords {
+            x: x as u32,
+            y: y as u32,
+        };
+        if let Some(person) = people.entities.get(&coords) {
+            debug!("Selected person {}", person.index());
+            selected.0 = Some(*person);
+        }
+    }
+}
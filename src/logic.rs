@@ -1,10 +1,32 @@
+pub mod abilities;
 mod ai;
+pub mod childhood;
 pub mod components;
+pub mod decisions;
+pub mod disease;
+pub mod genetics;
+pub mod goap;
+pub mod health;
 mod interactions;
 pub(crate) mod invariants;
+pub mod knowledge;
+pub mod learning;
+pub mod lineage;
 mod measures;
+pub mod mortality;
+mod names;
+pub mod partnership;
+pub mod pathfinding;
 pub mod people;
+pub mod personality;
 pub mod planet;
+pub mod pregnancy;
+pub mod relationships;
+#[cfg(test)]
+pub(crate) mod testing;
+pub mod thinkers;
+pub mod tribes;
+pub mod vision;
 
 pub use self::measures::{GeometryType, RealCoords, VirtualCoords};
 
@@ -17,11 +39,43 @@ impl Plugin for LogicPlugin {
         app.add_plugin(people::PeoplePlugin)
             .add_plugin(ai::AiPlugin)
             .insert_resource(planet::TotalTicks(0))
+            .init_resource::<relationships::Relationships>()
+            .init_resource::<tribes::Tribes>()
+            .init_resource::<tribes::Territory>()
+            .init_resource::<vision::VisionCache>()
             .add_system(planet::time_system.in_base_set(CoreSet::PreUpdate))
             .add_system(planet::food_growth)
             .add_system(interactions::add_interaction_system.in_base_set(CoreSet::First))
             .add_system(interactions::breeding_interaction_system)
+            .add_system(pregnancy::pregnancy_system)
+            .add_system(pregnancy::birth_cooldown_system)
+            .add_system(partnership::partnership_system)
+            .add_system(partnership::household_sharing_system)
+            .add_system(partnership::end_partnerships_system)
+            .add_system(childhood::assign_guardian_system)
+            .add_system(childhood::orphan_system)
+            .add_system(childhood::grow_up_system)
+            .add_system(childhood::follow_guardian_system)
+            .add_system(childhood::feeding_system)
+            .add_system(childhood::child_mortality_system)
+            .add_system(relationships::relationship_system)
+            .add_system(relationships::relationship_decay_system)
+            .add_system(knowledge::gossip_system)
+            .add_system(knowledge::forgetting_system)
+            .add_system(tribes::tribe_formation_system)
+            .add_system(tribes::tribe_birth_system)
+            .add_system(tribes::tribe_membership_system)
+            .add_system(tribes::territory_system)
+            .add_system(tribes::tribe_sharing_system)
             .add_system(interactions::trade_interaction_system)
-            .add_system(interactions::cleanup_interactions_system.in_base_set(CoreSet::PostUpdate));
+            .add_system(disease::outbreak_system)
+            .add_system(disease::transmission_system)
+            .add_system(disease::incubation_system)
+            .add_system(disease::sickness_system)
+            .add_system(disease::immunity_system)
+            .add_system(health::health_system)
+            .add_system(interactions::cleanup_interactions_system.in_base_set(CoreSet::PostUpdate))
+            .add_system(lineage::register_births_system)
+            .add_system(lineage::register_deaths_system);
     }
 }
@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::logic::genetics::Genome;
+use crate::logic::people::{Child, Old};
+use crate::logic::personality::{vision_range, Personality};
+
+/// Value of an ability in each stage of life, adults usually have 1.0
+#[derive(Copy, Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct AbilityCurve {
+    pub child: f32,
+    pub adult: f32,
+    pub old: f32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LifeStage {
+    Child,
+    Adult,
+    Old,
+}
+
+impl LifeStage {
+    pub fn of(child: Option<&Child>, old: Option<&Old>) -> LifeStage {
+        match (child, old) {
+            (Some(_), _) => LifeStage::Child,
+            (None, Some(_)) => LifeStage::Old,
+            (None, None) => LifeStage::Adult,
+        }
+    }
+
+    pub fn ability(&self, curve: &AbilityCurve) -> f32 {
+        match self {
+            LifeStage::Child => curve.child,
+            LifeStage::Adult => curve.adult,
+            LifeStage::Old => curve.old,
+        }
+    }
+
+    /// Probability of making a step in a frame
+    pub fn speed(&self, config: &Config) -> f32 {
+        self.ability(&config.abilities.movement_speed.value)
+    }
+
+    pub fn vision(
+        &self,
+        genome: &Genome,
+        personality: Option<&Personality>,
+        config: &Config,
+    ) -> u32 {
+        let vision = self.ability(&config.abilities.vision_range.value);
+        (genome.vision(vision_range(personality, config)) as f32 * vision).round() as u32
+    }
+
+    /// Fruits picked at once, fractional yields are picked with a matching probability
+    pub fn harvest(&self, config: &Config, roll: f32) -> u32 {
+        harvest(self.ability(&config.abilities.foraging_yield.value), roll)
+    }
+
+    /// Probability of noticing a favourable trade
+    pub fn trading_skill(&self, config: &Config) -> f32 {
+        self.ability(&config.abilities.trading_skill.value)
+            .clamp(0.0, 1.0)
+    }
+}
+
+fn harvest(expected: f32, roll: f32) -> u32 {
+    let expected = expected.max(0.0);
+    let whole = expected.floor();
+    whole as u32 + u32::from(roll < expected - whole)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn children_should_be_children_even_when_old() {
+        assert_eq!(LifeStage::of(Some(&Child), Some(&Old)), LifeStage::Child);
+        assert_eq!(LifeStage::of(None, Some(&Old)), LifeStage::Old);
+        assert_eq!(LifeStage::of(None, None), LifeStage::Adult);
+    }
+
+    #[test]
+    fn fractional_yield_should_be_picked_with_its_probability() {
+        assert_eq!(harvest(1.0, 0.99), 1);
+        assert_eq!(harvest(1.5, 0.4), 2);
+        assert_eq!(harvest(1.5, 0.6), 1);
+        assert_eq!(harvest(0.0, 0.0), 0);
+        assert_eq!(harvest(-1.0, 0.0), 0);
+    }
+}
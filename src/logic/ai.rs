@@ -1,8 +1,28 @@
 use crate::config::Config;
 use crate::debug::components::Performance;
+use crate::logic::abilities::LifeStage;
+use crate::logic::childhood::Guardian;
 use crate::logic::components::{FoodSource, Lookup};
-use crate::logic::measures::VirtualCoords;
-use crate::logic::people::{mark_entity_as_dead, Information, Knowledge, MoveTo};
+use crate::logic::decisions::Decisions;
+use crate::logic::genetics::Genome;
+use crate::logic::goap::{goap_eat_system, goap_planning_system, DecisionMaking, GoapAgent, Plan};
+use crate::logic::interactions::calculate_marginal_rate_of_substitution;
+use crate::logic::knowledge::{Information, Knowledge};
+use crate::logic::learning::{
+    learned_eat_system, learner_death_system, learning_system, Learner, Policy, POLICY_PATH,
+};
+use crate::logic::measures::{RealCoords, VirtualCoords};
+use crate::logic::partnership::Partners;
+use crate::logic::pathfinding::Grid;
+use crate::logic::people::{Age, Child, Female, Fertile, Migrant, MoveTo, Old};
+use crate::logic::personality::{
+    food_amount_goal, food_amount_threshold, Personalities, Personality, PERSONALITIES_PATH,
+};
+use crate::logic::planet::{freshest_growing_row, FoodType, Season, TotalTicks};
+use crate::logic::pregnancy::{BirthCooldown, Pregnant};
+use crate::logic::thinkers::{ActionDefinition, Thinkers, THINKERS_PATH};
+use crate::logic::tribes::{Territory, TribeMember};
+use crate::logic::vision::{in_sight, VisionCache};
 use bevy::prelude::*;
 use big_brain::prelude::*;
 use big_brain::BigBrainPlugin;
@@ -15,34 +35,91 @@ use super::components::{FoodAmount, Hunger, Person};
 use super::people::Forage;
 
 #[derive(Clone, Component, Debug, ScorerBuilder)]
-struct Hungry;
+pub struct Hungry;
 
 #[derive(Clone, Component, Debug, ActionBuilder)]
 pub struct Eat;
 
 #[derive(Clone, Component, Debug, ScorerBuilder)]
-struct MoveNeed;
+pub struct MoveNeed {
+    /// Overrides the food amount threshold of the AI configuration
+    pub food_threshold: Option<f32>,
+}
 
-#[derive(Clone, Component, Debug, ActionBuilder)]
-struct MoveAction;
+/// Travels to the best known food source and forages there once arrived
+#[derive(Clone, Component, Debug, Default, ActionBuilder)]
+pub struct MoveAction {
+    destination: Option<VirtualCoords>,
+}
 
 #[derive(Clone, Component, Debug, ScorerBuilder)]
-struct MissingInfo;
+pub struct MissingInfo;
 
 #[derive(Clone, Component, Debug, ActionBuilder)]
-struct LookAround;
+pub struct LookAround;
+
+#[derive(Clone, Component, Debug, ScorerBuilder)]
+pub struct TradeNeed;
+
+/// Travels next to someone to trade with and succeeds once arrived
+#[derive(Clone, Component, Debug, Default, ActionBuilder)]
+pub struct SeekTrade {
+    destination: Option<VirtualCoords>,
+}
+
+#[derive(Clone, Component, Debug, ScorerBuilder)]
+pub struct MatingDesire;
+
+/// Travels next to a possible mate and succeeds once arrived
+#[derive(Clone, Component, Debug, Default, ActionBuilder)]
+pub struct SeekMate {
+    destination: Option<VirtualCoords>,
+}
+
+#[derive(Clone, Component, Debug, ScorerBuilder)]
+pub struct SeasonEnding;
+
+/// Travels towards the rows where the growing season started most recently
+#[derive(Clone, Component, Debug, Default, ActionBuilder)]
+pub struct Migrate {
+    destination: Option<VirtualCoords>,
+}
 
 pub struct AiPlugin;
 
 impl Plugin for AiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugin(BigBrainPlugin)
+        let thinkers = Thinkers::load(THINKERS_PATH).expect("Unable to load thinkers");
+        debug!("Read thinkers: {:?}", thinkers);
+        let personalities = Personalities::load(PERSONALITIES_PATH, &thinkers)
+            .expect("Unable to load personalities");
+        debug!("Read personalities: {:?}", personalities);
+        let policy = Policy::load(POLICY_PATH).unwrap_or_else(|e| {
+            warn!("{}, learners start without experience", e);
+            Policy::default()
+        });
+        app.insert_resource(thinkers)
+            .insert_resource(personalities)
+            .insert_resource(policy)
+            .add_plugin(BigBrainPlugin)
             .add_system(eat_action_system.in_set(BigBrainSet::Actions))
             .add_system(hungry_scorer_system.in_set(BigBrainSet::Scorers))
             .add_system(move_action_system.in_set(BigBrainSet::Actions))
             .add_system(move_scorer_system.in_set(BigBrainSet::Scorers))
             .add_system(look_around_action_system.in_set(BigBrainSet::Actions))
             .add_system(missing_info_scorer_system.in_set(BigBrainSet::Scorers))
+            .add_system(seek_trade_action_system.in_set(BigBrainSet::Actions))
+            .add_system(trade_need_scorer_system.in_set(BigBrainSet::Scorers))
+            .add_system(seek_mate_action_system.in_set(BigBrainSet::Actions))
+            .add_system(mating_desire_scorer_system.in_set(BigBrainSet::Scorers))
+            .add_system(migrate_action_system.in_set(BigBrainSet::Actions))
+            .add_system(season_ending_scorer_system.in_set(BigBrainSet::Scorers))
+            .add_system(decision_scores_system.in_set(BigBrainSet::Thinkers))
+            .add_system(goap_planning_system)
+            .add_system(goap_eat_system.after(goap_planning_system))
+            .add_system(learning_system)
+            .add_system(learned_eat_system.after(learning_system))
+            .add_system(learner_death_system)
             .add_system(init_brains);
     }
 }
@@ -50,32 +127,49 @@ impl Plugin for AiPlugin {
 #[allow(clippy::type_complexity)]
 pub fn init_brains(
     mut commands: Commands,
-    query: Query<Entity, (With<Person>, Without<ThinkerBuilder>, Without<Dead>)>,
+    query: Query<
+        Entity,
+        (
+            With<Person>,
+            Without<ThinkerBuilder>,
+            Without<GoapAgent>,
+            Without<Learner>,
+            Without<Dead>,
+        ),
+    >,
+    thinkers: Res<Thinkers>,
+    personalities: Res<Personalities>,
+    config: Res<Config>,
 ) {
+    let thinker = thinkers.get(&config.ai.thinker.value);
     for entity in query.iter() {
-        info!("Adding a thinker @{}", entity.index());
-        commands.entity(entity).insert(
-            Thinker::build()
-                .picker(FirstToScore { threshold: 0.8 })
-                .when(Hungry, Eat)
-                // .when(MissingInfo, LookAround)
-                .when(MoveNeed, MoveAction),
-        );
-    }
-}
-
-#[measured]
-fn brain_wash(mut query: Query<(Entity, &mut Knowledge)>) {
-    for (entity, mut knowledge) in query.iter_mut() {
-        if !knowledge.infos.is_empty() {
-            info!("{} is brain washed", entity.index());
-            // remove half of random elements from knowledge
-            let mut rng = thread_rng();
-            let mut to_remove = knowledge.infos.len() / 2;
-            while to_remove > 0 {
-                let index = rng.gen_range(0..knowledge.infos.len());
-                knowledge.infos.remove(index);
-                to_remove -= 1;
+        match config.ai.decision_making.value {
+            DecisionMaking::Utility if config.ai.personalities.value => {
+                let (name, profile) = personalities.pick(thread_rng().gen());
+                info!("Adding a thinker of a {} @{}", name, entity.index());
+                commands.entity(entity).insert((
+                    thinkers.get(&profile.thinker).to_builder(),
+                    Personality::new(name, profile),
+                    Decisions::default(),
+                ));
+            }
+            DecisionMaking::Utility => {
+                info!("Adding a thinker @{}", entity.index());
+                commands
+                    .entity(entity)
+                    .insert((thinker.to_builder(), Decisions::default()));
+            }
+            DecisionMaking::Goap => {
+                info!("Adding a planner @{}", entity.index());
+                commands
+                    .entity(entity)
+                    .insert((GoapAgent, Plan::default(), Decisions::default()));
+            }
+            DecisionMaking::Learned => {
+                info!("Adding a learner @{}", entity.index());
+                commands
+                    .entity(entity)
+                    .insert((Learner::default(), Decisions::default()));
             }
         }
     }
@@ -100,152 +194,984 @@ fn missing_info_scorer_system(
 }
 
 #[measured]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn look_around_action_system(
     config: Res<Config>,
     food_lookup: Res<Lookup<FoodSource>>,
+    mut vision: ResMut<VisionCache>,
+    food: Query<&FoodAmount, With<FoodSource>>,
     mut query: Query<(&Actor, &mut ActionState), With<LookAround>>,
-    people: Query<&VirtualCoords>,
-    mut commands: Commands,
+    mut people: Query<(
+        &VirtualCoords,
+        &Genome,
+        &mut Knowledge,
+        Option<&Child>,
+        Option<&Old>,
+        Option<&Personality>,
+    )>,
+    mut decisions: Query<&mut Decisions>,
+    time: Res<TotalTicks>,
 ) {
     for (Actor(actor), state) in query.iter_mut() {
         just_execute(state, || {
-            if let Ok(coords) = people.get(*actor) {
-                let food = find_food(&food_lookup, &config, coords, config.ai.vision_range.value);
+            record(&mut decisions, *actor, time.0, ActionDefinition::LookAround);
+            if let Ok((coords, genome, mut knowledge, child, old, personality)) =
+                people.get_mut(*actor)
+            {
+                let food = find_food(
+                    &mut vision,
+                    &food_lookup,
+                    &food,
+                    &config,
+                    coords,
+                    LifeStage::of(child, old).vision(genome, personality, &config),
+                    time.0,
+                );
                 debug!("{} found {} food sources", actor.index(), food.len());
-                commands.entity(*actor).insert(Knowledge { infos: food });
+                remember(&mut knowledge, food, &config);
             }
         })
     }
 }
 
-fn find_food(
-    food_lookup: &Res<Lookup<FoodSource>>,
+pub(crate) fn remember(knowledge: &mut Knowledge, seen: Vec<Information>, config: &Config) {
+    for info in seen {
+        knowledge.learn(info, config.social.knowledge_capacity.value as usize);
+    }
+}
+
+pub(crate) fn find_food(
+    vision: &mut VisionCache,
+    food_lookup: &Lookup<FoodSource>,
+    food: &Query<&FoodAmount, With<FoodSource>>,
     config: &Config,
     origin: &VirtualCoords,
     vision_range: u32,
+    time: u32,
 ) -> Vec<Information> {
-    let mut result = Vec::new();
-    // gather GridCoords in a vector using coords, looking up, down, left, right upto vision_range tiles
-    let mut coords_to_check = Vec::new();
-    for x in origin.x - vision_range as i32..=origin.x + vision_range as i32 {
-        coords_to_check.push(VirtualCoords { x, y: origin.y });
-    }
-    for y in origin.y - vision_range as i32..=origin.y + vision_range as i32 {
-        coords_to_check.push(VirtualCoords { x: origin.x, y });
-    }
-    for coords in coords_to_check {
-        if let Some(food) = food_lookup.entities.get(&coords.to_real(config)) {
-            result.push(Information {
-                entity: *food,
-                coords,
-            });
-        }
-    }
-    result
+    let sightings = vision.food_sources(config, food_lookup, origin.to_real(config), vision_range);
+    sightings
+        .iter()
+        .filter_map(|sighting| {
+            let amount = food.get(sighting.entity).ok()?;
+            Some(Information {
+                entity: sighting.entity,
+                // relative to the person, so wrapped edges do not send them the long way around
+                coords: VirtualCoords {
+                    x: origin.x + sighting.dx,
+                    y: origin.y + sighting.dy,
+                },
+                observed: time,
+                reliability: 1.0,
+                apples: amount.apples,
+                oranges: amount.oranges,
+            })
+        })
+        .collect()
 }
 
 #[measured]
-fn move_action_system(
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(crate) fn move_action_system(
     mut commands: Commands,
-    // knowledge: Query<&Knowledge>,
+    mut knowledge: Query<&mut Knowledge>,
     food_lookup: Res<Lookup<FoodSource>>,
+    person_lookup: Res<Lookup<Person>>,
+    mut vision: ResMut<VisionCache>,
     food: Query<&FoodAmount, With<FoodSource>>,
-    person: Query<(&FoodAmount, &VirtualCoords), With<Person>>,
+    person: Query<
+        (
+            (&FoodAmount, &VirtualCoords, &Genome, Option<&MoveTo>),
+            (
+                Option<&TribeMember>,
+                Option<&Child>,
+                Option<&Old>,
+                Option<&Personality>,
+            ),
+        ),
+        With<Person>,
+    >,
+    territory: Res<Territory>,
+    time: Res<TotalTicks>,
     config: Res<Config>,
-    mut query: Query<(&Actor, &mut ActionState, &MoveAction)>,
+    mut decisions: Query<&mut Decisions>,
+    mut query: Query<(&Actor, &mut ActionState, &mut MoveAction)>,
 ) {
-    let mut random = thread_rng();
-    for (Actor(actor), state, _) in query.iter_mut() {
-        just_execute(state, || {
-            let destination = if let Ok((person_food, coords)) = person.get(*actor) {
-                let mut best = None;
-                let mut best_score = 0.0;
-                for info in find_food(&food_lookup, &config, coords, config.ai.vision_range.value) {
-                    if let Ok(food_amount) = food.get(info.entity) {
-                        let move_vector = VirtualCoords {
-                            x: info.coords.x - coords.x,
-                            y: info.coords.y - coords.y,
-                        };
-                        let cost = (move_vector.x.abs() + move_vector.y.abs()) as f32
-                            * config.game.hunger_increase.value;
-                        let max_food_of_type = config.ai.food_amount_goal.value / 2;
-                        let apple_preference = if person_food.apples < max_food_of_type {
-                            1.0 - person_food.apples as f32
-                                / (1.0 + person_food.oranges as f32 + person_food.apples as f32)
-                        } else {
-                            0.0
-                        };
-                        let orange_preference = if person_food.oranges < max_food_of_type {
-                            1.0 - apple_preference
-                        } else {
-                            0.0
-                        };
-                        let score = apple_preference * food_amount.apples as f32
-                            + orange_preference * food_amount.oranges as f32
-                            - cost;
-                        if score > best_score {
-                            best_score = score;
-                            best = Some(info.coords);
-                        }
+    for (Actor(actor), mut state, mut action) in query.iter_mut() {
+        let Ok(((person_food, coords, genome, moving), (member, child, old, personality))) =
+            person.get(*actor)
+        else {
+            // the actor could have died while this action was still running
+            debug!("{} is not a person with coords", actor.index());
+            if matches!(
+                *state,
+                ActionState::Requested | ActionState::Executing | ActionState::Cancelled
+            ) {
+                *state = ActionState::Failure;
+            }
+            continue;
+        };
+        match *state {
+            ActionState::Requested => {
+                record(&mut decisions, *actor, time.0, ActionDefinition::Move);
+                let seen = find_food(
+                    &mut vision,
+                    &food_lookup,
+                    &food,
+                    &config,
+                    coords,
+                    LifeStage::of(child, old).vision(genome, personality, &config),
+                    time.0,
+                );
+                let destination = if let Ok(mut knowledge) = knowledge.get_mut(*actor) {
+                    // people remember what they have seen and go to places they remember,
+                    // even beyond their vision, as long as they still believe the memory
+                    remember(&mut knowledge, seen, &config);
+                    let candidates = food_candidates(
+                        &knowledge,
+                        person_food,
+                        genome,
+                        coords,
+                        member,
+                        personality,
+                        &territory,
+                        time.0,
+                        &config,
+                    );
+                    let destination = best_destination(&candidates);
+                    if let Ok(mut decisions) = decisions.get_mut(*actor) {
+                        decisions.destinations = candidates;
                     }
-                }
-                debug!("{:?} has best score of {}", best, best_score);
-                best
-            } else {
-                panic!("{} is not a person with coords", actor.index());
-            };
-
-            let destination = if let Some(destination) = destination {
-                debug!(
-                    "{} is moving to best position found {:?}",
-                    actor.index(),
                     destination
-                );
-                destination
-            } else {
-                // randomize dx, dy as -1, 0, 1 (no diagonal movement)
-                let (dx, dy);
-                if random.gen_range(0..=1) == 0 {
-                    // horizontal move
-                    dx = random.gen_range(-1..=1);
-                    dy = 0;
                 } else {
-                    // vertical move
-                    dx = 0;
-                    dy = random.gen_range(-1..=1);
+                    None
+                };
+                let destination = if let Some(destination) = destination {
+                    debug!(
+                        "{} is moving to best position found {:?}",
+                        actor.index(),
+                        destination
+                    );
+                    destination
+                } else {
+                    random_step(coords)
+                };
+                commands.entity(*actor).insert(MoveTo::new(destination));
+                action.destination = Some(destination);
+                *state = ActionState::Executing;
+            }
+            ActionState::Executing => {
+                let arrived = travel(
+                    &mut commands,
+                    *actor,
+                    &mut state,
+                    action.destination,
+                    coords,
+                    moving.is_some(),
+                    &person_lookup,
+                    &config,
+                );
+                if arrived {
+                    debug!("{} forages at {:?}", actor.index(), coords);
+                    commands.entity(*actor).insert(Forage);
                 }
-                if let Ok((_, coords)) = person.get(*actor) {
-                    VirtualCoords {
-                        x: coords.x + dx,
-                        y: coords.y + dy,
-                    }
+            }
+            ActionState::Cancelled => {
+                debug!("{} stops moving", actor.index());
+                commands.entity(*actor).remove::<MoveTo>();
+                *state = ActionState::Failure;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Follows the move of an executing action until the person stands on the destination, which
+/// succeeds the action and returns true, or fails it when the destination can not be reached
+#[allow(clippy::too_many_arguments)]
+fn travel(
+    commands: &mut Commands,
+    actor: Entity,
+    state: &mut ActionState,
+    destination: Option<VirtualCoords>,
+    coords: &VirtualCoords,
+    moving: bool,
+    person_lookup: &Lookup<Person>,
+    config: &Config,
+) -> bool {
+    let Some(destination) = destination else {
+        *state = ActionState::Failure;
+        return false;
+    };
+    match progress(
+        commands,
+        actor,
+        &destination,
+        coords,
+        moving,
+        person_lookup,
+        config,
+    ) {
+        Progress::Arrived => {
+            *state = ActionState::Success;
+            true
+        }
+        Progress::Travelling => false,
+        Progress::Stopped => {
+            *state = ActionState::Failure;
+            false
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Progress {
+    Arrived,
+    Travelling,
+    Stopped,
+}
+
+/// How the move of a person to the destination goes, the move is ended on arrival and when the
+/// destination is taken by someone else
+pub(crate) fn progress(
+    commands: &mut Commands,
+    actor: Entity,
+    destination: &VirtualCoords,
+    coords: &VirtualCoords,
+    moving: bool,
+    person_lookup: &Lookup<Person>,
+    config: &Config,
+) -> Progress {
+    let to = destination.to_real(config);
+    if coords.to_real(config) == to {
+        debug!("{} arrived at {:?}", actor.index(), to);
+        commands.entity(actor).remove::<MoveTo>();
+        return Progress::Arrived;
+    }
+    if !moving {
+        // no path was found or the move was stopped by someone else
+        debug!("{} did not reach {:?}", actor.index(), to);
+        Progress::Stopped
+    } else if person_lookup
+        .entities
+        .get(&to)
+        .is_some_and(|other| *other != actor)
+    {
+        // waiting for the destination to be freed could take forever, so choose again
+        debug!("{} finds {:?} occupied", actor.index(), to);
+        commands.entity(actor).remove::<MoveTo>();
+        Progress::Stopped
+    } else {
+        Progress::Travelling
+    }
+}
+
+/// Starts foraging when the person reaches the food source they went to, returns the food source
+/// while they are still on their way
+pub(crate) fn forage_on_arrival(
+    commands: &mut Commands,
+    actor: Entity,
+    food_source: Option<VirtualCoords>,
+    coords: &VirtualCoords,
+    moving: bool,
+    person_lookup: &Lookup<Person>,
+    config: &Config,
+) -> Option<VirtualCoords> {
+    let food_source = food_source?;
+    match progress(
+        commands,
+        actor,
+        &food_source,
+        coords,
+        moving,
+        person_lookup,
+        config,
+    ) {
+        Progress::Arrived => {
+            debug!("{} forages at {:?}", actor.index(), coords);
+            commands.entity(actor).insert(Forage);
+            None
+        }
+        Progress::Travelling => Some(food_source),
+        Progress::Stopped => None,
+    }
+}
+
+/// Candidate food source worth the most after the cost of getting there
+pub(crate) fn best_destination(candidates: &[(VirtualCoords, f32)]) -> Option<VirtualCoords> {
+    let mut best = None;
+    let mut best_score = 0.0;
+    for (coords, score) in candidates {
+        if *score > best_score {
+            best_score = *score;
+            best = Some(*coords);
+        }
+    }
+    debug!("{:?} has best score of {}", best, best_score);
+    best
+}
+
+/// Remembered food sources outside foreign territories, scored by the food a person wants there
+/// minus the cost of getting there
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn food_candidates(
+    knowledge: &Knowledge,
+    person_food: &FoodAmount,
+    genome: &Genome,
+    coords: &VirtualCoords,
+    member: Option<&TribeMember>,
+    personality: Option<&Personality>,
+    territory: &Territory,
+    time: u32,
+    config: &Config,
+) -> Vec<(VirtualCoords, f32)> {
+    let grid = Grid::from_config(config);
+    let max_food_of_type =
+        stockpile_goal(food_amount_goal(personality, config), coords, time, config) / 2;
+    let apple_preference = if person_food.apples < max_food_of_type {
+        1.0 - person_food.apples as f32
+            / (1.0 + person_food.oranges as f32 + person_food.apples as f32)
+    } else {
+        0.0
+    };
+    let orange_preference = if person_food.oranges < max_food_of_type {
+        1.0 - apple_preference
+    } else {
+        0.0
+    };
+    // innate preference of 0.5 leaves the inventory based preferences unchanged
+    let apple_preference = apple_preference * 2.0 * genome.apple_preference;
+    let orange_preference = orange_preference * 2.0 * (1.0 - genome.apple_preference);
+    let mut candidates = Vec::new();
+    for info in knowledge.infos.iter() {
+        // there is no point in going to trees guarded by another tribe
+        if territory.excludes(info.coords.to_real(config), member, config) {
+            continue;
+        }
+        // the way around wrapped edges of the map may be shorter
+        let distance = grid.distance(coords.to_real(config), info.coords.to_real(config));
+        let cost = distance as f32 * config.game.hunger_increase.value;
+        let confidence = info.confidence(time, config.social.memory_strength.value);
+        let score = confidence
+            * (apple_preference * info.apples as f32 + orange_preference * info.oranges as f32)
+            - cost;
+        candidates.push((info.coords, score));
+    }
+    candidates
+}
+
+/// Whether the person stands on a tree with the fruit they want: the one they are starving for,
+/// otherwise the one they have less of
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn at_wanted_food(
+    hunger: &Hunger,
+    person_food: &FoodAmount,
+    origin: RealCoords,
+    member: Option<&TribeMember>,
+    territory: &Territory,
+    food_lookup: &Lookup<FoodSource>,
+    food: &Query<&FoodAmount, With<FoodSource>>,
+    sources: &Query<&FoodSource>,
+    config: &Config,
+) -> bool {
+    let wants_apples = if hunger.apple > 1.0 && person_food.apples == 0 {
+        true
+    } else if hunger.orange > 1.0 && person_food.oranges == 0 {
+        false
+    } else {
+        person_food.apples <= person_food.oranges
+    };
+    !territory.excludes(origin, member, config)
+        && food_lookup
+            .entities
+            .get(&origin)
+            .and_then(|source| Some((food.get(*source).ok()?, sources.get(*source).ok()?)))
+            .is_some_and(|(amount, source)| match source.0 {
+                FoodType::Apple => wants_apples && amount.apples > 0,
+                FoodType::Orange => !wants_apples && amount.oranges > 0,
+            })
+}
+
+/// One step in a random direction, or staying in place
+pub(crate) fn random_step(coords: &VirtualCoords) -> VirtualCoords {
+    let mut random = thread_rng();
+    // randomize dx, dy as -1, 0, 1 (no diagonal movement)
+    let (dx, dy);
+    if random.gen_range(0..=1) == 0 {
+        // horizontal move
+        dx = random.gen_range(-1..=1);
+        dy = 0;
+    } else {
+        // vertical move
+        dx = 0;
+        dy = random.gen_range(-1..=1);
+    }
+    VirtualCoords {
+        x: coords.x + dx,
+        y: coords.y + dy,
+    }
+}
+
+/// Logarithm of the marginal rate of substitution, negative for people rich in apples and
+/// positive for people rich in oranges, `None` for people with nothing to trade
+pub(crate) fn trade_imbalance(food: &FoodAmount) -> Option<f32> {
+    let mrs = calculate_marginal_rate_of_substitution(food.apples, food.oranges);
+    if mrs.is_nan() {
+        None
+    } else {
+        Some(mrs.ln())
+    }
+}
+
+/// How much a trade with someone of given imbalance is worth, only people rich in what the
+/// actor lacks are likely to accept it
+pub(crate) fn trade_weight(own: f32, theirs: Option<f32>) -> Option<f32> {
+    let theirs = theirs?;
+    if own * theirs < 0.0 {
+        Some(theirs.abs().min(own.abs()))
+    } else {
+        None
+    }
+}
+
+pub(crate) type Traders<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static FoodAmount,
+        &'static VirtualCoords,
+        &'static Genome,
+        Option<&'static Child>,
+        Option<&'static Old>,
+        Option<&'static Personality>,
+    ),
+>;
+
+/// Free tile next to the visible person whose holdings complement the actor's the most, `None`
+/// when there is nobody to trade with in sight
+fn trade_destination(sight: &mut Sight, traders: &Traders, actor: Entity) -> Option<VirtualCoords> {
+    let (food, coords, genome, child, old, personality) = traders.get(actor).ok()?;
+    let own = trade_imbalance(food)?;
+    let range = LifeStage::of(child, old).vision(genome, personality, sight.config);
+    sight.approach_best(coords, range, |other| {
+        trade_weight(
+            own,
+            traders
+                .get(other)
+                .ok()
+                .and_then(|(food, ..)| trade_imbalance(food)),
+        )
+    })
+}
+
+/// Grows with the imbalance of the holdings, zero when there is nobody to trade with in sight so
+/// that the person looks for food instead
+#[measured]
+#[allow(clippy::too_many_arguments)]
+fn trade_need_scorer_system(
+    people_lookup: Res<Lookup<Person>>,
+    food_lookup: Res<Lookup<FoodSource>>,
+    mut vision: ResMut<VisionCache>,
+    traders: Traders,
+    mut query: Query<(&Actor, &mut Score), With<TradeNeed>>,
+    cared_for: Query<&Guardian>,
+    config: Res<Config>,
+) {
+    let mut sight = Sight {
+        vision: &mut vision,
+        config: &config,
+        people_lookup: &people_lookup,
+        food_lookup: &food_lookup,
+    };
+    for (Actor(actor), mut score) in query.iter_mut() {
+        let imbalance = traders
+            .get(*actor)
+            .ok()
+            .and_then(|(food, ..)| trade_imbalance(food));
+        let s = match imbalance {
+            Some(imbalance)
+                if config.game.trade_allowed.value && cared_for.get(*actor).is_err() =>
+            {
+                clamp(imbalance.abs() / config.ai.trade_need_ratio.value.ln())
+            }
+            _ => 0.0,
+        };
+        let s = if s > 0.0 && trade_destination(&mut sight, &traders, *actor).is_none() {
+            0.0
+        } else {
+            s
+        };
+        debug!("{} has score of {} for trading", actor.index(), s);
+        score.set(s);
+    }
+}
+
+/// Moves next to the visible person whose holdings complement the actor's the most, fails when
+/// there is nobody to trade with in sight
+#[measured]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn seek_trade_action_system(
+    mut commands: Commands,
+    people_lookup: Res<Lookup<Person>>,
+    food_lookup: Res<Lookup<FoodSource>>,
+    mut vision: ResMut<VisionCache>,
+    traders: Traders,
+    moving: Query<&MoveTo>,
+    config: Res<Config>,
+    mut decisions: Query<&mut Decisions>,
+    time: Res<TotalTicks>,
+    mut query: Query<(&Actor, &mut ActionState, &mut SeekTrade)>,
+) {
+    let mut sight = Sight {
+        vision: &mut vision,
+        config: &config,
+        people_lookup: &people_lookup,
+        food_lookup: &food_lookup,
+    };
+    for (Actor(actor), mut state, mut action) in query.iter_mut() {
+        let Ok((_, coords, ..)) = traders.get(*actor) else {
+            if matches!(
+                *state,
+                ActionState::Requested | ActionState::Executing | ActionState::Cancelled
+            ) {
+                *state = ActionState::Failure;
+            }
+            continue;
+        };
+        match *state {
+            ActionState::Requested => {
+                record(&mut decisions, *actor, time.0, ActionDefinition::SeekTrade);
+                if let Some(destination) = trade_destination(&mut sight, &traders, *actor) {
+                    debug!("{} is moving to {:?} to trade", actor.index(), destination);
+                    commands.entity(*actor).insert(MoveTo::new(destination));
+                    action.destination = Some(destination);
+                    *state = ActionState::Executing;
                 } else {
-                    panic!("{} is not a person with coords", actor.index());
+                    *state = ActionState::Failure;
                 }
+            }
+            ActionState::Executing => {
+                travel(
+                    &mut commands,
+                    *actor,
+                    &mut state,
+                    action.destination,
+                    coords,
+                    moving.get(*actor).is_ok(),
+                    &people_lookup,
+                    &config,
+                );
+            }
+            ActionState::Cancelled => {
+                debug!("{} stops looking for a trade", actor.index());
+                commands.entity(*actor).remove::<MoveTo>();
+                *state = ActionState::Failure;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// What a person needs to look for other people around them
+pub(crate) struct Sight<'a> {
+    pub vision: &'a mut VisionCache,
+    pub config: &'a Config,
+    pub people_lookup: &'a Lookup<Person>,
+    pub food_lookup: &'a Lookup<FoodSource>,
+}
+
+impl Sight<'_> {
+    /// Other people within the vision range of the tile, with their offsets, nearest first
+    fn people(&mut self, origin: RealCoords, range: u32) -> Vec<(Entity, i32, i32)> {
+        let config = self.config;
+        let grid = Grid::from_config(config);
+        self.vision
+            .offsets(config, range)
+            .iter()
+            .filter(|(dx, dy)| (*dx, *dy) != (0, 0))
+            .filter(|(dx, dy)| {
+                !config.ai.line_of_sight.value
+                    || in_sight(&grid, self.food_lookup, origin, *dx, *dy)
+            })
+            .filter_map(|(dx, dy)| {
+                let tile = grid.offset(origin, *dx, *dy)?;
+                self.people_lookup
+                    .entities
+                    .get(&tile)
+                    .map(|person| (*person, *dx, *dy))
+            })
+            .collect()
+    }
+
+    /// Free tile next to the visible person with the highest weight for its distance, people
+    /// without a weight are ignored
+    pub fn approach_best(
+        &mut self,
+        coords: &VirtualCoords,
+        range: u32,
+        weight: impl Fn(Entity) -> Option<f32>,
+    ) -> Option<VirtualCoords> {
+        let grid = Grid::from_config(self.config);
+        let origin = coords.to_real(self.config);
+        let mut best = None;
+        let mut best_score = 0.0;
+        for (other, dx, dy) in self.people(origin, range) {
+            let Some(weight) = weight(other) else {
+                continue;
             };
-            commands
-                .entity(*actor)
-                .insert(MoveTo { dest: destination })
-                // todo this should be first added after the move is ended
-                .insert(Forage);
+            let score = weight / (1 + dx.abs() + dy.abs()) as f32;
+            if score <= best_score {
+                continue;
+            }
+            if let Some((x, y)) = approach(&grid, self.people_lookup, origin, dx, dy) {
+                best_score = score;
+                best = Some(VirtualCoords {
+                    x: coords.x + x,
+                    y: coords.y + y,
+                });
+            }
+        }
+        best
+    }
+}
+
+/// Offset of a free tile next to the person at given offset, `None` when the actor is already
+/// standing next to them or there is no room around them
+fn approach(
+    grid: &Grid,
+    people_lookup: &Lookup<Person>,
+    origin: RealCoords,
+    dx: i32,
+    dy: i32,
+) -> Option<(i32, i32)> {
+    if dx.abs() + dy.abs() <= 1 {
+        return None;
+    }
+    [(1, 0), (-1, 0), (0, 1), (0, -1)]
+        .into_iter()
+        .map(|(nx, ny)| (dx + nx, dy + ny))
+        .find(|(x, y)| {
+            grid.offset(origin, *x, *y)
+                .is_some_and(|tile| !people_lookup.entities.contains_key(&tile))
         })
+}
+
+/// Mating desire of a fertile person grows with the food they can share with a baby and is the
+/// strongest in the middle of their fertile years
+pub(crate) fn mating_desire(
+    food: &FoodAmount,
+    age: &Age,
+    female: Option<&Female>,
+    config: &Config,
+) -> f32 {
+    let game = &config.game;
+    let (min_age, max_age) = if female.is_some() {
+        (
+            game.min_fertile_age_female.value,
+            game.max_fertile_age_female.value,
+        )
+    } else {
+        (
+            game.min_fertile_age_male.value,
+            game.max_fertile_age_male.value,
+        )
+    };
+    let reserves = food.apples.min(food.oranges) as f32 / game.food_for_baby.value as f32;
+    let years = (max_age.saturating_sub(min_age)).max(1) as f32;
+    let progress = age.0.saturating_sub(min_age) as f32 / years;
+    let prime = 1.0 - 0.5 * (2.0 * progress - 1.0).abs();
+    clamp(reserves) * prime
+}
+
+#[measured]
+#[allow(clippy::type_complexity)]
+fn mating_desire_scorer_system(
+    people: Query<
+        (&FoodAmount, &Age, Option<&Female>),
+        (With<Fertile>, Without<Pregnant>, Without<BirthCooldown>),
+    >,
+    mut query: Query<(&Actor, &mut Score), With<MatingDesire>>,
+    config: Res<Config>,
+) {
+    for (Actor(actor), mut score) in query.iter_mut() {
+        let s = if let Ok((food, age, female)) = people.get(*actor) {
+            mating_desire(food, age, female, &config)
+        } else {
+            0.0
+        };
+        debug!("{} has score of {} for mating", actor.index(), s);
+        score.set(s);
+    }
+}
+
+pub(crate) type MateCandidates<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Option<&'static Female>,
+        Option<&'static Pregnant>,
+        Option<&'static BirthCooldown>,
+    ),
+    (With<Person>, With<Fertile>),
+>;
+
+/// Fertile people of the opposite sex who can conceive, partners are worth twice as much
+pub(crate) fn mate_weight(
+    candidates: &MateCandidates,
+    other: Entity,
+    female: bool,
+    partners: Option<&Partners>,
+    config: &Config,
+) -> Option<f32> {
+    let (other_female, pregnant, cooldown) = candidates.get(other).ok()?;
+    if female == other_female.is_some() || pregnant.is_some() || cooldown.is_some() {
+        return None;
+    }
+    let partner = partners.is_some_and(|p| p.contains(other));
+    if partner {
+        Some(2.0)
+    } else if config.social.reproduce_only_with_partner.value {
+        None
+    } else {
+        Some(1.0)
     }
 }
 
+/// Moves next to a visible fertile person of the opposite sex, partners first, fails when there
+/// is nobody in sight
 #[measured]
-fn move_scorer_system(
-    food_amount: Query<&FoodAmount>,
-    mut query: Query<(&Actor, &mut Score), With<MoveNeed>>,
-    already_moving: Query<(&Actor, &MoveTo)>,
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn seek_mate_action_system(
+    mut commands: Commands,
+    people_lookup: Res<Lookup<Person>>,
+    food_lookup: Res<Lookup<FoodSource>>,
+    mut vision: ResMut<VisionCache>,
+    seekers: Query<(
+        &VirtualCoords,
+        &Genome,
+        Option<&Female>,
+        Option<&Partners>,
+        (Option<&Child>, Option<&Old>, Option<&Personality>),
+    )>,
+    candidates: MateCandidates,
+    moving: Query<&MoveTo>,
+    config: Res<Config>,
+    mut decisions: Query<&mut Decisions>,
+    time: Res<TotalTicks>,
+    mut query: Query<(&Actor, &mut ActionState, &mut SeekMate)>,
+) {
+    let mut sight = Sight {
+        vision: &mut vision,
+        config: &config,
+        people_lookup: &people_lookup,
+        food_lookup: &food_lookup,
+    };
+    for (Actor(actor), mut state, mut action) in query.iter_mut() {
+        let Ok((coords, genome, female, partners, (child, old, personality))) = seekers.get(*actor)
+        else {
+            if matches!(
+                *state,
+                ActionState::Requested | ActionState::Executing | ActionState::Cancelled
+            ) {
+                *state = ActionState::Failure;
+            }
+            continue;
+        };
+        match *state {
+            ActionState::Requested => {
+                record(&mut decisions, *actor, time.0, ActionDefinition::SeekMate);
+                let range = LifeStage::of(child, old).vision(genome, personality, &config);
+                let best = sight.approach_best(coords, range, |other| {
+                    mate_weight(&candidates, other, female.is_some(), partners, &config)
+                });
+                if let Some(destination) = best {
+                    debug!(
+                        "{} is moving to {:?} to find a mate",
+                        actor.index(),
+                        destination
+                    );
+                    commands.entity(*actor).insert(MoveTo::new(destination));
+                    action.destination = Some(destination);
+                    *state = ActionState::Executing;
+                } else {
+                    *state = ActionState::Failure;
+                }
+            }
+            ActionState::Executing => {
+                travel(
+                    &mut commands,
+                    *actor,
+                    &mut state,
+                    action.destination,
+                    coords,
+                    moving.get(*actor).is_ok(),
+                    &people_lookup,
+                    &config,
+                );
+            }
+            ActionState::Cancelled => {
+                debug!("{} stops looking for a mate", actor.index());
+                commands.entity(*actor).remove::<MoveTo>();
+                *state = ActionState::Failure;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Food amount goal raised while the growing season of the row of the person is about to end or
+/// over, as trees there do not grow new fruit
+pub(crate) fn stockpile_goal(goal: u32, coords: &VirtualCoords, time: u32, config: &Config) -> u32 {
+    let horizon = config.ai.season_horizon.value;
+    if horizon > 0 && Season::at(coords, time, config).ending_within(horizon) {
+        (goal as f32 * config.ai.stockpile_factor.value).round() as u32
+    } else {
+        goal
+    }
+}
+
+/// Grows as the end of the growing season of the row of the person approaches, full when the
+/// season is over
+#[measured]
+fn season_ending_scorer_system(
+    people: Query<&VirtualCoords>,
+    mut query: Query<(&Actor, &mut Score), With<SeasonEnding>>,
+    cared_for: Query<&Guardian>,
+    time: Res<TotalTicks>,
     config: Res<Config>,
 ) {
+    let horizon = config.ai.season_horizon.value;
     for (Actor(actor), mut score) in query.iter_mut() {
-        if already_moving.get(*actor).is_ok() {
-            debug!("{} is already moving", actor.index());
+        let Ok(coords) = people.get(*actor) else {
+            continue;
+        };
+        let s = if horizon == 0 || cared_for.get(*actor).is_ok() {
+            0.0
+        } else {
+            match Season::at(coords, time.0, &config) {
+                Season::Growing { ticks_left } => clamp(1.0 - ticks_left as f32 / horizon as f32),
+                Season::Dormant { .. } => 1.0,
+            }
+        };
+        debug!("{} has score of {} for migrating", actor.index(), s);
+        score.set(s);
+    }
+}
+
+/// Moves at most as far as the person sees towards the freshest growing rows, the person is
+/// marked as a migrant from then on
+#[measured]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn migrate_action_system(
+    mut commands: Commands,
+    person_lookup: Res<Lookup<Person>>,
+    people: Query<
+        (
+            (&VirtualCoords, &Genome, Option<&MoveTo>),
+            (Option<&Child>, Option<&Old>, Option<&Personality>),
+        ),
+        With<Person>,
+    >,
+    time: Res<TotalTicks>,
+    config: Res<Config>,
+    mut decisions: Query<&mut Decisions>,
+    mut query: Query<(&Actor, &mut ActionState, &mut Migrate)>,
+) {
+    let grid = Grid::from_config(&config);
+    for (Actor(actor), mut state, mut action) in query.iter_mut() {
+        let Ok(((coords, genome, moving), (child, old, personality))) = people.get(*actor) else {
+            if matches!(
+                *state,
+                ActionState::Requested | ActionState::Executing | ActionState::Cancelled
+            ) {
+                *state = ActionState::Failure;
+            }
+            continue;
+        };
+        match *state {
+            ActionState::Requested => {
+                record(&mut decisions, *actor, time.0, ActionDefinition::Migrate);
+                let Some(row) = freshest_growing_row(
+                    time.0,
+                    config.map.size_y.value,
+                    config.game.year_length.value,
+                    config.game.growing_season_length.value,
+                ) else {
+                    *state = ActionState::Failure;
+                    continue;
+                };
+                let size = config.map.size_y.value as i32;
+                let mut dy = row as i32 - coords.to_real(&config).y as i32;
+                if grid.wrap_y && dy.abs() > size / 2 {
+                    dy -= dy.signum() * size;
+                }
+                let range = LifeStage::of(child, old).vision(genome, personality, &config) as i32;
+                let destination = VirtualCoords {
+                    x: coords.x,
+                    y: coords.y + dy.clamp(-range, range),
+                };
+                if destination == *coords {
+                    *state = ActionState::Success;
+                    continue;
+                }
+                debug!(
+                    "{} migrates to {:?} following the season",
+                    actor.index(),
+                    destination
+                );
+                commands
+                    .entity(*actor)
+                    .insert((MoveTo::new(destination), Migrant));
+                action.destination = Some(destination);
+                *state = ActionState::Executing;
+            }
+            ActionState::Executing => {
+                travel(
+                    &mut commands,
+                    *actor,
+                    &mut state,
+                    action.destination,
+                    coords,
+                    moving.is_some(),
+                    &person_lookup,
+                    &config,
+                );
+            }
+            ActionState::Cancelled => {
+                debug!("{} stops migrating", actor.index());
+                commands.entity(*actor).remove::<MoveTo>();
+                *state = ActionState::Failure;
+            }
+            _ => {}
+        }
+    }
+}
+
+#[measured]
+fn move_scorer_system(
+    food_amount: Query<(&FoodAmount, &VirtualCoords)>,
+    mut query: Query<(&Actor, &mut Score, &MoveNeed)>,
+    personalities: Query<&Personality>,
+    cared_for: Query<&Guardian>,
+    time: Res<TotalTicks>,
+    config: Res<Config>,
+) {
+    for (Actor(actor), mut score, move_need) in query.iter_mut() {
+        // the need stays while moving, so the move goes on until something more urgent wins
+        if cared_for.get(*actor).is_ok() {
+            // children stay with their guardian instead of looking for food
             score.set(0.0);
-        } else if let Ok(food) = food_amount.get(*actor) {
-            let food_goal = config.ai.food_amount_goal.value;
-            let food_threshold = config.ai.food_amount_threshold.value;
+        } else if let Ok((food, coords)) = food_amount.get(*actor) {
+            let personality = personalities.get(*actor).ok();
+            let food_goal = stockpile_goal(
+                food_amount_goal(personality, &config),
+                coords,
+                time.0,
+                &config,
+            );
+            let food_threshold = move_need
+                .food_threshold
+                .unwrap_or_else(|| food_amount_threshold(personality, &config));
             let s = clamp(
                 (max(
                     food_goal as i32 - food.apples as i32,
@@ -262,59 +1188,66 @@ fn move_scorer_system(
 
 #[measured]
 pub fn eat_action_system(
-    mut commands: Commands,
     mut hungers: Query<(&mut Hunger, &mut FoodAmount)>,
     mut query: Query<(&Actor, &mut ActionState, &Eat)>,
+    mut decisions: Query<&mut Decisions>,
+    time: Res<TotalTicks>,
     config: Res<Config>,
 ) {
     for (Actor(actor), state, _eat) in query.iter_mut() {
         if let Ok((mut hunger, mut food)) = hungers.get_mut(*actor) {
             trace!("{} is eating", actor.index());
             just_execute(state, || {
-                if hunger.apple > 1.0 && food.apples > 0 {
-                    let old_hunger = hunger.apple;
-                    hunger.apple -= config.game.hunger_decrease.value;
-                    food.apples -= 1;
-                    trace!(
-                        "Person ate something, food left: {}, hunger for apples was: {}, hunger for apples is: {}",
-                        food.apples + food.oranges,
-                        old_hunger,
-                        hunger.apple
-                    );
-                } else if hunger.orange > 1.0 && food.oranges > 0 {
-                    let old_hunger = hunger.orange;
-                    hunger.orange -= config.game.hunger_decrease.value;
-                    food.oranges -= 1;
-                    trace!(
-                        "Person ate something, food left: {}, hunger for oranges was: {}, hunger for oranges is: {}",
-                        food.apples + food.oranges,
-                        old_hunger,
-                        hunger.orange
-                    );
-                } else {
-                    mark_entity_as_dead(*actor, &mut commands, &config);
-                    if hunger.orange > 1.0 {
-                        info!("Person {} has died of orange hunger", actor.index());
-                    } else if hunger.apple > 1.0 {
-                        info!("Person {} has died of apple hunger", actor.index());
-                    } else {
-                        info!("Person {} has died of unknown reason", actor.index());
-                    }
+                record(&mut decisions, *actor, time.0, ActionDefinition::Eat);
+                if !eat(&mut hunger, &mut food, &config) {
+                    // starving people lose health in health_system until they find food
+                    debug!("{} has nothing to eat", actor.index());
                 }
             });
         }
     }
 }
 
+/// Eats one fruit the person is hungry for, returns false when there is no such fruit
+pub(crate) fn eat(hunger: &mut Hunger, food: &mut FoodAmount, config: &Config) -> bool {
+    if hunger.apple > 1.0 && food.apples > 0 {
+        let old_hunger = hunger.apple;
+        hunger.apple -= config.game.hunger_decrease.value;
+        food.apples -= 1;
+        trace!(
+            "Person ate something, food left: {}, hunger for apples was: {}, hunger for apples is: {}",
+            food.apples + food.oranges,
+            old_hunger,
+            hunger.apple
+        );
+        true
+    } else if hunger.orange > 1.0 && food.oranges > 0 {
+        let old_hunger = hunger.orange;
+        hunger.orange -= config.game.hunger_decrease.value;
+        food.oranges -= 1;
+        trace!(
+            "Person ate something, food left: {}, hunger for oranges was: {}, hunger for oranges is: {}",
+            food.apples + food.oranges,
+            old_hunger,
+            hunger.orange
+        );
+        true
+    } else {
+        false
+    }
+}
+
 #[measured]
 fn hungry_scorer_system(
-    hungers: Query<&Hunger>,
+    hungers: Query<(&Hunger, &FoodAmount)>,
     mut query: Query<(&Actor, &mut Score), With<Hungry>>,
 ) {
     for (Actor(actor), mut score) in query.iter_mut() {
-        if let Ok(hunger) = hungers.get(*actor) {
-            // eat only if hunger is above 1.0, if nothihg to eat entity will die
-            let s = if hunger.apple > 1.0 || hunger.orange > 1.0 {
+        if let Ok((hunger, food)) = hungers.get(*actor) {
+            // eat only if hunger is above 1.0 and there is matching food, otherwise go find some
+            let s = if (hunger.apple > 1.0 && food.apples > 0)
+                || (hunger.orange > 1.0 && food.oranges > 0)
+            {
                 1.0
             } else {
                 0.0
@@ -324,6 +1257,55 @@ fn hungry_scorer_system(
     }
 }
 
+/// Copies the values of the scorers to the decisions of their people, composite scorers of
+/// big_brain are left out as they only combine these
+#[measured]
+#[allow(clippy::type_complexity)]
+fn decision_scores_system(
+    scorers: Query<(
+        &Actor,
+        &Score,
+        AnyOf<(
+            &Hungry,
+            &MoveNeed,
+            &MissingInfo,
+            &TradeNeed,
+            &MatingDesire,
+            &SeasonEnding,
+        )>,
+    )>,
+    mut decisions: Query<&mut Decisions, With<ThinkerBuilder>>,
+) {
+    for mut decisions in decisions.iter_mut() {
+        decisions.scores.clear();
+    }
+    for (Actor(actor), score, kind) in scorers.iter() {
+        let name = match kind {
+            (Some(_), _, _, _, _, _) => "Hungry",
+            (_, Some(_), _, _, _, _) => "MoveNeed",
+            (_, _, Some(_), _, _, _) => "MissingInfo",
+            (_, _, _, Some(_), _, _) => "TradeNeed",
+            (_, _, _, _, Some(_), _) => "MatingDesire",
+            _ => "SeasonEnding",
+        };
+        if let Ok(mut decisions) = decisions.get_mut(*actor) {
+            decisions.scores.push((name.to_string(), score.get()));
+        }
+    }
+}
+
+/// Remembers an action started by a person
+fn record(
+    decisions: &mut Query<&mut Decisions>,
+    actor: Entity,
+    time: u32,
+    action: ActionDefinition,
+) {
+    if let Ok(mut decisions) = decisions.get_mut(actor) {
+        decisions.act(time, action);
+    }
+}
+
 fn just_execute(mut state: Mut<ActionState>, f: impl FnOnce()) {
     match *state {
         ActionState::Requested => {
@@ -348,3 +1330,241 @@ fn clamp(x: f32) -> f32 {
         0.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::people::{foraging_system, move_system};
+    use crate::logic::testing::{world_with_known_tree, TREE};
+    use crate::logic::GeometryType;
+
+    /// The person knowing about a tree a few tiles away, who was asked to move to food
+    fn world() -> (World, Entity, Entity) {
+        let (mut world, person) = world_with_known_tree();
+        let action = world
+            .spawn((Actor(person), ActionState::Requested, MoveAction::default()))
+            .id();
+        (world, person, action)
+    }
+
+    fn tick() -> Schedule {
+        let mut schedule = Schedule::new();
+        schedule.add_systems(
+            (
+                move_action_system,
+                migrate_action_system,
+                apply_system_buffers,
+                move_system,
+                apply_system_buffers,
+                foraging_system,
+                apply_system_buffers,
+            )
+                .chain(),
+        );
+        schedule
+    }
+
+    fn apples(world: &World, person: Entity) -> u32 {
+        world.get::<FoodAmount>(person).unwrap().apples
+    }
+
+    #[test]
+    fn move_should_forage_only_at_destination() {
+        let (mut world, person, action) = world();
+        let mut schedule = tick();
+        schedule.run(&mut world);
+        assert_eq!(
+            *world.get::<ActionState>(action).unwrap(),
+            ActionState::Executing
+        );
+        assert!(world.get::<Forage>(person).is_none());
+        for _ in 0..10 {
+            if *world.get::<ActionState>(action).unwrap() != ActionState::Executing {
+                break;
+            }
+            assert_eq!(apples(&world, person), 0);
+            schedule.run(&mut world);
+        }
+        assert_eq!(
+            *world.get::<ActionState>(action).unwrap(),
+            ActionState::Success
+        );
+        assert_eq!(*world.get::<VirtualCoords>(person).unwrap(), TREE);
+        assert!(world.get::<MoveTo>(person).is_none());
+        assert_eq!(apples(&world, person), 1);
+    }
+
+    #[test]
+    fn cancelled_move_should_stop_without_foraging() {
+        let (mut world, person, action) = world();
+        let mut schedule = tick();
+        schedule.run(&mut world);
+        *world.get_mut::<ActionState>(action).unwrap() = ActionState::Cancelled;
+        schedule.run(&mut world);
+        assert_eq!(
+            *world.get::<ActionState>(action).unwrap(),
+            ActionState::Failure
+        );
+        assert!(world.get::<MoveTo>(person).is_none());
+        assert_ne!(*world.get::<VirtualCoords>(person).unwrap(), TREE);
+        assert_eq!(apples(&world, person), 0);
+    }
+
+    #[test]
+    fn migrants_should_move_towards_the_growing_season() {
+        let (mut world, person, action) = world();
+        world.despawn(action);
+        let migrate = world
+            .spawn((Actor(person), ActionState::Requested, Migrate::default()))
+            .id();
+        // the first row is out of the growing season and it started last further down the map
+        world.resource_mut::<TotalTicks>().0 = 200;
+        world.resource_mut::<Config>().map.geometry.value = GeometryType::FlatEarth;
+        let config = world.resource::<Config>();
+        assert!(matches!(
+            Season::at(&VirtualCoords { x: 0, y: 0 }, 200, config),
+            Season::Dormant { .. }
+        ));
+        let row = freshest_growing_row(
+            200,
+            config.map.size_y.value,
+            config.game.year_length.value,
+            config.game.growing_season_length.value,
+        )
+        .unwrap();
+        let mut schedule = tick();
+        schedule.run(&mut world);
+        assert_eq!(
+            *world.get::<ActionState>(migrate).unwrap(),
+            ActionState::Executing
+        );
+        assert!(world.get::<Migrant>(person).is_some());
+        for _ in 0..20 {
+            if *world.get::<ActionState>(migrate).unwrap() != ActionState::Executing {
+                break;
+            }
+            schedule.run(&mut world);
+        }
+        assert_eq!(
+            *world.get::<ActionState>(migrate).unwrap(),
+            ActionState::Success
+        );
+        let coords = *world.get::<VirtualCoords>(person).unwrap();
+        assert_eq!(coords.x, 0);
+        assert!(coords.y > 0 && coords.y as u32 <= row, "{:?}", coords);
+        assert!(world.get::<Forage>(person).is_none());
+    }
+
+    #[test]
+    fn imbalance_should_tell_which_fruit_is_lacking() {
+        let food = |apples, oranges| FoodAmount { apples, oranges };
+        assert!(trade_imbalance(&food(9, 1)).unwrap() < 0.0);
+        assert!(trade_imbalance(&food(1, 9)).unwrap() > 0.0);
+        assert_eq!(trade_imbalance(&food(4, 4)), Some(0.0));
+        assert_eq!(trade_imbalance(&food(0, 9)), Some(f32::INFINITY));
+        assert_eq!(trade_imbalance(&food(0, 0)), None);
+    }
+
+    #[test]
+    fn only_complementary_people_should_be_worth_trading_with() {
+        assert_eq!(trade_weight(-2.0, Some(1.0)), Some(1.0));
+        assert_eq!(trade_weight(3.0, Some(-2.0)), Some(2.0));
+        assert_eq!(trade_weight(-2.0, Some(-1.0)), None);
+        assert_eq!(trade_weight(-2.0, Some(0.0)), None);
+        assert_eq!(trade_weight(-2.0, None), None);
+    }
+
+    #[test]
+    fn trade_need_should_be_zero_without_anybody_to_trade_with() {
+        let (mut world, person, action) = world();
+        world.despawn(action);
+        world.resource_mut::<Config>().game.trade_allowed.value = true;
+        *world.get_mut::<FoodAmount>(person).unwrap() = FoodAmount {
+            apples: 0,
+            oranges: 9,
+        };
+        let scorer = world
+            .spawn((Actor(person), Score::default(), TradeNeed))
+            .id();
+        let mut schedule = Schedule::new();
+        schedule.add_system(trade_need_scorer_system);
+        schedule.run(&mut world);
+        assert_eq!(world.get::<Score>(scorer).unwrap().get(), 0.0);
+
+        let coords = VirtualCoords { x: 0, y: 3 };
+        let other = world
+            .spawn((
+                Person,
+                coords,
+                FoodAmount {
+                    apples: 9,
+                    oranges: 1,
+                },
+                Genome::default(),
+            ))
+            .id();
+        let tile = coords.to_real(world.resource::<Config>());
+        world
+            .resource_mut::<Lookup<Person>>()
+            .entities
+            .insert(tile, other);
+        schedule.run(&mut world);
+        assert_eq!(world.get::<Score>(scorer).unwrap().get(), 1.0);
+    }
+
+    #[test]
+    fn seek_trade_should_go_on_until_arrival() {
+        let (mut world, person, action) = world();
+        world.despawn(action);
+        *world.get_mut::<FoodAmount>(person).unwrap() = FoodAmount {
+            apples: 0,
+            oranges: 9,
+        };
+        let coords = VirtualCoords { x: 0, y: 4 };
+        let other = world
+            .spawn((
+                Person,
+                coords,
+                FoodAmount {
+                    apples: 9,
+                    oranges: 1,
+                },
+                Genome::default(),
+            ))
+            .id();
+        let tile = coords.to_real(world.resource::<Config>());
+        world
+            .resource_mut::<Lookup<Person>>()
+            .entities
+            .insert(tile, other);
+        let seek = world
+            .spawn((Actor(person), ActionState::Requested, SeekTrade::default()))
+            .id();
+        let mut schedule = Schedule::new();
+        schedule.add_systems(
+            (
+                seek_trade_action_system,
+                apply_system_buffers,
+                move_system,
+                apply_system_buffers,
+            )
+                .chain(),
+        );
+        for _ in 0..10 {
+            schedule.run(&mut world);
+            if *world.get::<ActionState>(seek).unwrap() != ActionState::Executing {
+                break;
+            }
+        }
+        assert_eq!(
+            *world.get::<ActionState>(seek).unwrap(),
+            ActionState::Success
+        );
+        let arrived = *world.get::<VirtualCoords>(person).unwrap();
+        assert_eq!(
+            (arrived.x - coords.x).abs() + (arrived.y - coords.y).abs(),
+            1
+        );
+        assert!(world.get::<MoveTo>(person).is_none());
+    }
+}
@@ -0,0 +1,220 @@
+use crate::debug::components::Performance;
+use bevy::prelude::*;
+use macros::measured;
+use rand::seq::IteratorRandom;
+use rand::thread_rng;
+use std::collections::hash_map::Entry;
+
+use crate::config::Config;
+use crate::logic::components::{Dead, FoodAmount, Lookup, Person};
+use crate::logic::lineage::{FamilyName, Parents};
+use crate::logic::measures::{RealCoords, VirtualCoords};
+use crate::logic::partnership::Partners;
+use crate::logic::pathfinding::Grid;
+use crate::logic::people::{Child, MoveTo};
+use crate::stats::components::Statistics;
+
+/// Adult who keeps the child nearby and feeds it. Children without a guardian live on their own.
+#[derive(Component)]
+pub struct Guardian(pub Entity);
+
+/// Child that lost its guardian and nobody took care of it
+#[derive(Component)]
+pub struct Orphan;
+
+/// Newborn children are taken care of by their mother, or by their father if she is already dead
+#[measured]
+pub fn assign_guardian_system(
+    mut commands: Commands,
+    children: Query<(Entity, &Parents), Added<Child>>,
+    adults: Query<Entity, (With<Person>, Without<Child>)>,
+) {
+    for (child, parents) in children.iter() {
+        if let Some(guardian) = [parents.mother, parents.father]
+            .into_iter()
+            .find(|parent| adults.get(*parent).is_ok())
+        {
+            commands.entity(child).insert(Guardian(guardian));
+        }
+    }
+}
+
+/// When a guardian dies the child goes to the other parent, then to the guardian's partner
+/// and then to any adult of the same family. If nobody is left the child becomes an orphan.
+#[measured]
+#[allow(clippy::type_complexity)]
+pub fn orphan_system(
+    mut commands: Commands,
+    mut children: Query<
+        (Entity, &mut Guardian, Option<&Parents>, &FamilyName),
+        (With<Child>, With<Person>),
+    >,
+    adults: Query<(Entity, &FamilyName), (With<Person>, Without<Child>)>,
+    partners: Query<&Partners>,
+) {
+    for (child, mut guardian, parents, family_name) in children.iter_mut() {
+        if adults.get(guardian.0).is_ok() {
+            continue;
+        }
+        let is_alive_adult = |candidate: &Entity| adults.get(*candidate).is_ok();
+        let new_guardian = parents
+            .and_then(|p| [p.mother, p.father].into_iter().find(is_alive_adult))
+            .or_else(|| {
+                partners
+                    .get(guardian.0)
+                    .ok()
+                    .and_then(|p| p.0.iter().map(|p| p.partner).find(is_alive_adult))
+            })
+            .or_else(|| {
+                adults
+                    .iter()
+                    .filter(|(_, name)| *name == family_name)
+                    .map(|(adult, _)| adult)
+                    .choose(&mut thread_rng())
+            });
+        if let Some(new_guardian) = new_guardian {
+            debug!(
+                "{} takes care of {} after {} died",
+                new_guardian.index(),
+                child.index(),
+                guardian.0.index()
+            );
+            guardian.0 = new_guardian;
+        } else {
+            info!("{} became an orphan", child.index());
+            commands.entity(child).remove::<Guardian>().insert(Orphan);
+        }
+    }
+}
+
+/// Grown up children are on their own
+#[measured]
+#[allow(clippy::type_complexity)]
+pub fn grow_up_system(
+    mut commands: Commands,
+    adults: Query<Entity, (Or<(With<Guardian>, With<Orphan>)>, Without<Child>)>,
+) {
+    for adult in adults.iter() {
+        commands
+            .entity(adult)
+            .remove::<Guardian>()
+            .remove::<Orphan>();
+    }
+}
+
+/// Children wandering too far from their guardian walk back one tile each frame
+#[measured]
+#[allow(clippy::type_complexity)]
+pub fn follow_guardian_system(
+    mut commands: Commands,
+    children: Query<(Entity, &Guardian, &VirtualCoords), (With<Child>, With<Person>)>,
+    guardians: Query<&VirtualCoords, Without<Child>>,
+    config: Res<Config>,
+    mut person_lookup: ResMut<Lookup<Person>>,
+) {
+    let grid = Grid::from_config(&config);
+    for (child, guardian, coords) in children.iter() {
+        if let Ok(guardian_coords) = guardians.get(guardian.0) {
+            let from = coords.to_real(&config);
+            let to = guardian_coords.to_real(&config);
+            if grid.distance(from, to) <= config.game.guardian_distance.value {
+                continue;
+            }
+            let (dx, dy) = step_towards(&grid, from, to);
+            let new_position = VirtualCoords {
+                x: coords.x + dx,
+                y: coords.y + dy,
+            };
+            if let Entry::Vacant(slot) = person_lookup.entities.entry(new_position.to_real(&config))
+            {
+                commands
+                    .entity(child)
+                    .insert(new_position)
+                    .remove::<MoveTo>();
+                slot.insert(child);
+                person_lookup.entities.remove(&from);
+            }
+        }
+    }
+}
+
+/// Guardians hand over food to their children until they have the configured reserve
+#[measured]
+#[allow(clippy::type_complexity)]
+pub fn feeding_system(
+    children: Query<(Entity, &Guardian), (With<Child>, With<Person>)>,
+    mut food: Query<&mut FoodAmount, With<Person>>,
+    config: Res<Config>,
+) {
+    let reserve = config.game.child_food_reserve.value;
+    for (child, guardian) in children.iter() {
+        if let Ok([mut child_food, mut guardian_food]) = food.get_many_mut([child, guardian.0]) {
+            if child_food.apples < reserve && guardian_food.apples > 0 {
+                child_food.apples += 1;
+                guardian_food.apples -= 1;
+            }
+            if child_food.oranges < reserve && guardian_food.oranges > 0 {
+                child_food.oranges += 1;
+                guardian_food.oranges -= 1;
+            }
+        }
+    }
+}
+
+#[measured]
+#[allow(clippy::type_complexity)]
+pub fn child_mortality_system(
+    died: Query<Option<&Orphan>, (With<Child>, With<Dead>, Added<Dead>)>,
+    mut stats: ResMut<Statistics>,
+) {
+    let mut deaths = 0;
+    for orphan in died.iter() {
+        deaths += 1;
+        if orphan.is_some() {
+            stats.orphan_deaths += 1;
+        }
+    }
+    stats.child_deaths_history.push(deaths);
+}
+
+/// One tile step without diagonals, horizontal first, over the edges of the map where it wraps
+fn step_towards(grid: &Grid, from: RealCoords, to: RealCoords) -> (i32, i32) {
+    match grid.direction(from, to) {
+        (0, dy) => (0, dy),
+        (dx, _) => (dx, 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::GeometryType;
+
+    #[test]
+    fn should_step_horizontally_first() {
+        let grid = Grid::new(10, 10, GeometryType::FlatEarth);
+        let from = RealCoords { x: 5, y: 5 };
+        assert_eq!(step_towards(&grid, from, RealCoords { x: 8, y: 1 }), (1, 0));
+        assert_eq!(
+            step_towards(&grid, from, RealCoords { x: 2, y: 9 }),
+            (-1, 0)
+        );
+        assert_eq!(step_towards(&grid, from, RealCoords { x: 5, y: 9 }), (0, 1));
+        assert_eq!(step_towards(&grid, from, from), (0, 0));
+    }
+
+    #[test]
+    fn should_step_over_wrapped_edges() {
+        let grid = Grid::new(10, 10, GeometryType::Torus);
+        let from = RealCoords { x: 0, y: 0 };
+        assert_eq!(
+            step_towards(&grid, from, RealCoords { x: 9, y: 0 }),
+            (-1, 0)
+        );
+        assert_eq!(
+            step_towards(&grid, from, RealCoords { x: 0, y: 8 }),
+            (0, -1)
+        );
+        assert_eq!(grid.distance(from, RealCoords { x: 9, y: 9 }), 2);
+    }
+}
@@ -1,4 +1,4 @@
-pub use super::people::{Age, Dead, Hunger, MoveTo, Person};
+pub use super::people::{Age, Dead, Hunger, Person};
 pub use super::planet::{FoodAmount, FoodSource, FoodType};
 use crate::logic::measures::RealCoords;
 use bevy::prelude::*;
@@ -14,5 +14,6 @@ pub struct Ttl(pub u32);
 #[derive(Resource)]
 pub struct Lookup<T> {
     pub entities: HashMap<RealCoords, Entity>,
+    #[allow(dead_code)]
     pub default: Option<T>,
 }
@@ -0,0 +1,46 @@
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+use crate::logic::measures::VirtualCoords;
+
+/// Number of past actions kept for each person
+pub const ACTION_HISTORY: usize = 10;
+
+/// What the AI of a person considered recently, so odd behaviour can be inspected in the UI
+#[derive(Component, Default, Debug, Clone)]
+pub struct Decisions {
+    /// Current values of the scorers of the thinker, or the goal priorities of the planner
+    pub scores: Vec<(String, f32)>,
+    /// Latest actions with the tick they started at, newest first
+    pub actions: VecDeque<(u32, String)>,
+    /// Remembered food sources considered by the last move with their computed scores
+    pub destinations: Vec<(VirtualCoords, f32)>,
+}
+
+impl Decisions {
+    pub fn act(&mut self, tick: u32, action: impl ToString) {
+        self.actions.push_front((tick, action.to_string()));
+        self.actions.truncate(ACTION_HISTORY);
+    }
+
+    /// The action chosen most recently
+    pub fn current(&self) -> Option<&str> {
+        self.actions.front().map(|(_, action)| action.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_should_keep_latest_actions() {
+        let mut decisions = Decisions::default();
+        for tick in 0..(ACTION_HISTORY as u32 + 5) {
+            decisions.act(tick, format!("Action {}", tick));
+        }
+        assert_eq!(decisions.actions.len(), ACTION_HISTORY);
+        assert_eq!(decisions.current(), Some("Action 14"));
+        assert_eq!(decisions.actions.back().map(|(tick, _)| *tick), Some(5));
+    }
+}
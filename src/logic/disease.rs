@@ -0,0 +1,283 @@
+use crate::debug::components::Performance;
+use bevy::prelude::*;
+use macros::measured;
+use rand::prelude::IteratorRandom;
+use rand::{random, thread_rng};
+
+use crate::config::Config;
+use crate::logic::components::{Age, Hunger, Person};
+use crate::logic::interactions::PeopleInteraction;
+use crate::logic::people::{mark_entity_as_dead, CauseOfDeath};
+use crate::stats::components::Statistics;
+
+/// Infected but not yet infectious, becomes `Infectious` when `ticks_left` reaches zero
+#[derive(Component)]
+pub struct Exposed {
+    pub ticks_left: u32,
+}
+
+/// Sick and spreading the disease to neighbours
+#[derive(Component)]
+pub struct Infectious {
+    pub ticks_left: u32,
+}
+
+/// Recovered, can not be infected again until `ticks_left` reaches zero
+#[derive(Component)]
+pub struct Immune {
+    pub ticks_left: u32,
+}
+
+/// Randomly infects a susceptible person, so the disease can come back after it dies out
+#[measured]
+#[allow(clippy::type_complexity)]
+pub fn outbreak_system(
+    mut commands: Commands,
+    susceptible: Query<
+        Entity,
+        (
+            With<Person>,
+            Without<Exposed>,
+            Without<Infectious>,
+            Without<Immune>,
+        ),
+    >,
+    config: Res<Config>,
+) {
+    if random::<f32>() < config.disease.outbreak_probability.value {
+        if let Some(person) = susceptible.iter().choose(&mut thread_rng()) {
+            info!("Disease outbreak started by {}", person.index());
+            commands.entity(person).insert(Exposed {
+                ticks_left: config.disease.incubation_period.value,
+            });
+        }
+    }
+}
+
+#[measured]
+#[allow(clippy::type_complexity)]
+pub fn transmission_system(
+    mut commands: Commands,
+    interactions: Query<&PeopleInteraction>,
+    infectious: Query<&Infectious, With<Person>>,
+    susceptible: Query<
+        Entity,
+        (
+            With<Person>,
+            Without<Exposed>,
+            Without<Infectious>,
+            Without<Immune>,
+        ),
+    >,
+    config: Res<Config>,
+    mut stats: ResMut<Statistics>,
+) {
+    let mut infections = 0;
+    let mut infected = Vec::new();
+    for interaction in interactions.iter() {
+        if infectious.get(interaction.a).is_ok()
+            && susceptible.get(interaction.b).is_ok()
+            && !infected.contains(&interaction.b)
+            && random::<f32>() < config.disease.transmission_probability.value
+        {
+            debug!(
+                "{} infected {}",
+                interaction.a.index(),
+                interaction.b.index()
+            );
+            commands.entity(interaction.b).insert(Exposed {
+                ticks_left: config.disease.incubation_period.value,
+            });
+            infected.push(interaction.b);
+            infections += 1;
+        }
+    }
+    stats.infections_history.push(infections);
+}
+
+#[measured]
+pub fn incubation_system(
+    mut commands: Commands,
+    mut exposed: Query<(Entity, &mut Exposed), With<Person>>,
+    config: Res<Config>,
+) {
+    for (person, mut exposure) in exposed.iter_mut() {
+        if exposure.ticks_left > 0 {
+            exposure.ticks_left -= 1;
+        } else {
+            commands
+                .entity(person)
+                .remove::<Exposed>()
+                .insert(Infectious {
+                    ticks_left: config.disease.infectious_period.value,
+                });
+        }
+    }
+}
+
+/// Sick people get hungry faster and may die, survivors become immune
+#[measured]
+pub fn sickness_system(
+    mut commands: Commands,
+    mut sick: Query<(Entity, &mut Infectious, &mut Hunger, &Age), With<Person>>,
+    config: Res<Config>,
+) {
+    for (person, mut infection, mut hunger, age) in sick.iter_mut() {
+        hunger.apple += config.disease.sickness_hunger_increase.value;
+        hunger.orange += config.disease.sickness_hunger_increase.value;
+        if random::<f32>() < config.disease.sickness_mortality.value {
+            mark_entity_as_dead(person, CauseOfDeath::Disease, age, &mut commands, &config);
+            info!("Person {} has died of disease", person.index());
+        } else if infection.ticks_left > 0 {
+            infection.ticks_left -= 1;
+        } else {
+            debug!("{} recovered", person.index());
+            commands
+                .entity(person)
+                .remove::<Infectious>()
+                .insert(Immune {
+                    ticks_left: config.disease.immunity_duration.value,
+                });
+        }
+    }
+}
+
+#[measured]
+pub fn immunity_system(mut commands: Commands, mut immune: Query<(Entity, &mut Immune)>) {
+    for (person, mut immunity) in immune.iter_mut() {
+        if immunity.ticks_left > 0 {
+            immunity.ticks_left -= 1;
+        } else {
+            commands.entity(person).remove::<Immune>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::components::Dead;
+    use crate::logic::testing;
+
+    fn world() -> (World, Entity) {
+        let mut world = testing::world();
+        let mut config = world.resource_mut::<Config>();
+        config.disease.incubation_period.value = 1;
+        config.disease.infectious_period.value = 1;
+        config.disease.immunity_duration.value = 1;
+        config.disease.sickness_mortality.value = 0.0;
+        let person = world
+            .spawn((
+                Person,
+                Age(0),
+                Hunger {
+                    apple: 0.0,
+                    orange: 0.0,
+                },
+                Exposed { ticks_left: 1 },
+            ))
+            .id();
+        (world, person)
+    }
+
+    fn progression() -> Schedule {
+        let mut schedule = Schedule::new();
+        schedule.add_systems(
+            (
+                incubation_system,
+                apply_system_buffers,
+                sickness_system,
+                apply_system_buffers,
+                immunity_system,
+                apply_system_buffers,
+            )
+                .chain(),
+        );
+        schedule
+    }
+
+    #[test]
+    fn exposed_person_should_get_sick_recover_and_lose_immunity() {
+        let (mut world, person) = world();
+        let mut schedule = progression();
+        let states = (0..8)
+            .map(|_| {
+                schedule.run(&mut world);
+                (
+                    world.get::<Exposed>(person).is_some(),
+                    world.get::<Infectious>(person).is_some(),
+                    world.get::<Immune>(person).is_some(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let first = |state| states.iter().position(|s| *s == state).unwrap();
+        let sick = first((false, true, false));
+        let immune = first((false, false, true));
+        let susceptible = first((false, false, false));
+        assert!(sick < immune && immune < susceptible, "{:?}", states);
+        assert!(world.get::<Hunger>(person).unwrap().apple > 0.0);
+    }
+
+    #[test]
+    fn immune_person_should_not_be_infected() {
+        let (mut world, person) = world();
+        world
+            .entity_mut(person)
+            .remove::<Exposed>()
+            .insert(Immune { ticks_left: 10 });
+        let sick = world.spawn((Person, Infectious { ticks_left: 10 })).id();
+        world.spawn(PeopleInteraction { a: sick, b: person });
+        world
+            .resource_mut::<Config>()
+            .disease
+            .transmission_probability
+            .value = 1.0;
+        world.insert_resource(Statistics::default());
+        let mut schedule = Schedule::new();
+        schedule.add_systems((transmission_system, apply_system_buffers).chain());
+        schedule.run(&mut world);
+        assert!(world.get::<Exposed>(person).is_none());
+        world.entity_mut(person).remove::<Immune>();
+        schedule.run(&mut world);
+        assert!(world.get::<Exposed>(person).is_some());
+    }
+
+    #[test]
+    fn person_killed_twice_should_keep_the_first_cause() {
+        let (mut world, person) = world();
+        world
+            .entity_mut(person)
+            .remove::<Exposed>()
+            .insert(Infectious { ticks_left: 10 });
+        world
+            .resource_mut::<Config>()
+            .disease
+            .sickness_mortality
+            .value = 1.0;
+        let mut schedule = Schedule::new();
+        schedule.add_systems(
+            (
+                |mut commands: Commands, people: Query<(Entity, &Age)>, config: Res<Config>| {
+                    for (person, age) in people.iter() {
+                        mark_entity_as_dead(
+                            person,
+                            CauseOfDeath::OldAge,
+                            age,
+                            &mut commands,
+                            &config,
+                        );
+                    }
+                },
+                sickness_system,
+                apply_system_buffers,
+            )
+                .chain(),
+        );
+        schedule.run(&mut world);
+        assert!(world.get::<Dead>(person).is_some());
+        assert_eq!(
+            *world.get::<CauseOfDeath>(person).unwrap(),
+            CauseOfDeath::OldAge
+        );
+    }
+}
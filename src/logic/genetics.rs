@@ -0,0 +1,171 @@
+use bevy::prelude::*;
+use rand::{thread_rng, Rng};
+
+use crate::config::Config;
+
+const METABOLISM_RANGE: (f32, f32) = (0.2, 3.0);
+const VISION_RANGE: (f32, f32) = (0.0, 3.0);
+const FERTILITY_RANGE: (f32, f32) = (0.0, 1.0);
+const LIFESPAN_RANGE: (f32, f32) = (0.2, 3.0);
+const APPLE_PREFERENCE_RANGE: (f32, f32) = (0.0, 1.0);
+/// Number of traits listed by `Genome::traits`
+pub const TRAIT_COUNT: usize = 5;
+
+/// Heritable traits of a person. All of them except fertility and apple preference are
+/// multipliers of the matching configuration value, so 1.0 means an average person.
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct Genome {
+    /// Multiplier of hunger increase
+    pub metabolism: f32,
+    /// Multiplier of AI vision range
+    pub vision_range: f32,
+    /// Probability of conceiving when a couple has enough food for a baby
+    pub fertility: f32,
+    /// Multiplier of max person age
+    pub lifespan: f32,
+    /// 0.0 means only oranges are searched for, 1.0 only apples, 0.5 no preference
+    pub apple_preference: f32,
+}
+
+impl Default for Genome {
+    fn default() -> Self {
+        Genome {
+            metabolism: 1.0,
+            vision_range: 1.0,
+            fertility: 0.8,
+            lifespan: 1.0,
+            apple_preference: 0.5,
+        }
+    }
+}
+
+impl Genome {
+    pub fn random(variation: f32) -> Self {
+        let mut rng = thread_rng();
+        let average = Genome::default();
+        Genome {
+            metabolism: vary(&mut rng, average.metabolism, variation, METABOLISM_RANGE),
+            vision_range: vary(&mut rng, average.vision_range, variation, VISION_RANGE),
+            fertility: vary(&mut rng, average.fertility, variation, FERTILITY_RANGE),
+            lifespan: vary(&mut rng, average.lifespan, variation, LIFESPAN_RANGE),
+            apple_preference: vary(
+                &mut rng,
+                average.apple_preference,
+                variation,
+                APPLE_PREFERENCE_RANGE,
+            ),
+        }
+    }
+
+    /// Each trait is taken from a random parent and then mutated with given probability
+    /// by at most `mutation_strength` in either direction
+    pub fn inherit(
+        father: &Genome,
+        mother: &Genome,
+        mutation_probability: f32,
+        mutation_strength: f32,
+    ) -> Self {
+        let mut rng = thread_rng();
+        let mut pick = |from_father: f32, from_mother: f32, range: (f32, f32)| {
+            let value = if rng.gen_bool(0.5) {
+                from_father
+            } else {
+                from_mother
+            };
+            if rng.gen_range(0.0..1.0) < mutation_probability {
+                vary(&mut rng, value, mutation_strength, range)
+            } else {
+                value
+            }
+        };
+        Genome {
+            metabolism: pick(father.metabolism, mother.metabolism, METABOLISM_RANGE),
+            vision_range: pick(father.vision_range, mother.vision_range, VISION_RANGE),
+            fertility: pick(father.fertility, mother.fertility, FERTILITY_RANGE),
+            lifespan: pick(father.lifespan, mother.lifespan, LIFESPAN_RANGE),
+            apple_preference: pick(
+                father.apple_preference,
+                mother.apple_preference,
+                APPLE_PREFERENCE_RANGE,
+            ),
+        }
+    }
+
+    pub fn vision(&self, range: u32) -> u32 {
+        (range as f32 * self.vision_range).round() as u32
+    }
+
+    pub fn max_age(&self, config: &Config) -> u32 {
+        (config.game.max_person_age.value as f32 * self.lifespan) as u32
+    }
+
+    pub fn traits(&self) -> [(&'static str, f32); TRAIT_COUNT] {
+        [
+            ("Metabolism", self.metabolism),
+            ("Vision range", self.vision_range),
+            ("Fertility", self.fertility),
+            ("Lifespan", self.lifespan),
+            ("Apple preference", self.apple_preference),
+        ]
+    }
+}
+
+fn vary(rng: &mut impl Rng, value: f32, strength: f32, (min, max): (f32, f32)) -> f32 {
+    if strength <= 0.0 {
+        return value.clamp(min, max);
+    }
+    (value + rng.gen_range(-strength..strength)).clamp(min, max)
+}
+
+#[cfg(test)]
+extern crate quickcheck;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[quickcheck]
+    fn inherited_traits_should_stay_in_bounds(
+        mutation_probability: f32,
+        mutation_strength: f32,
+    ) -> bool {
+        if !(0.0..=1.0).contains(&mutation_probability) || !(0.0..=1.0).contains(&mutation_strength)
+        {
+            return true;
+        }
+        let father = Genome {
+            metabolism: METABOLISM_RANGE.1,
+            vision_range: VISION_RANGE.1,
+            fertility: FERTILITY_RANGE.1,
+            lifespan: LIFESPAN_RANGE.1,
+            apple_preference: APPLE_PREFERENCE_RANGE.1,
+        };
+        let mother = Genome {
+            metabolism: METABOLISM_RANGE.0,
+            vision_range: VISION_RANGE.0,
+            fertility: FERTILITY_RANGE.0,
+            lifespan: LIFESPAN_RANGE.0,
+            apple_preference: APPLE_PREFERENCE_RANGE.0,
+        };
+        let child = Genome::inherit(&father, &mother, mutation_probability, mutation_strength);
+        child
+            .traits()
+            .iter()
+            .zip(father.traits().iter().zip(mother.traits().iter()))
+            .all(|((_, value), ((_, max), (_, min)))| value >= min && value <= max)
+    }
+
+    #[test]
+    fn without_mutation_traits_should_come_from_parents() {
+        let father = Genome::random(0.5);
+        let mother = Genome::random(0.5);
+        let child = Genome::inherit(&father, &mother, 0.0, 0.5);
+        for ((_, value), ((_, from_father), (_, from_mother))) in child
+            .traits()
+            .iter()
+            .zip(father.traits().iter().zip(mother.traits().iter()))
+        {
+            assert!(value == from_father || value == from_mother);
+        }
+    }
+}
@@ -0,0 +1,584 @@
+use crate::debug::components::Performance;
+use bevy::prelude::*;
+use macros::measured;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+use std::fmt::Display;
+
+use crate::config::Config;
+use crate::logic::abilities::LifeStage;
+use crate::logic::ai::{
+    at_wanted_food, best_destination, eat, find_food, food_candidates, forage_on_arrival,
+    mate_weight, mating_desire, random_step, remember, stockpile_goal, trade_imbalance,
+    trade_weight, MateCandidates, Sight,
+};
+use crate::logic::childhood::Guardian;
+use crate::logic::components::{FoodAmount, FoodSource, Hunger, Lookup, Person};
+use crate::logic::decisions::Decisions;
+use crate::logic::genetics::Genome;
+use crate::logic::knowledge::Knowledge;
+use crate::logic::measures::VirtualCoords;
+use crate::logic::partnership::Partners;
+use crate::logic::pathfinding::Grid;
+use crate::logic::people::{Age, Child, Female, Fertile, Forage, MoveTo, Old};
+use crate::logic::planet::TotalTicks;
+use crate::logic::pregnancy::{BirthCooldown, Pregnant};
+use crate::logic::tribes::{Territory, TribeMember};
+use crate::logic::vision::VisionCache;
+
+/// Whether people are driven by big_brain thinkers, by plans towards their goals or by a
+/// learned policy
+#[derive(Copy, Clone, Serialize, Deserialize, Debug, Display, PartialEq, Eq)]
+pub enum DecisionMaking {
+    Utility,
+    Goap,
+    Learned,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Fact {
+    Fed,
+    HasFood,
+    KnowsFood,
+    AtFood,
+    Wealthy,
+    Balanced,
+    NearTrader,
+    ReadyForBaby,
+    NearMate,
+    Reproduced,
+}
+
+/// Facts a person believes about themselves and their surroundings
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WorldState(u16);
+
+impl WorldState {
+    pub fn get(self, fact: Fact) -> bool {
+        self.0 & (1 << fact as u16) != 0
+    }
+
+    pub fn with(self, fact: Fact, value: bool) -> WorldState {
+        if value {
+            WorldState(self.0 | 1 << fact as u16)
+        } else {
+            WorldState(self.0 & !(1 << fact as u16))
+        }
+    }
+
+    pub fn satisfies(self, conditions: &[(Fact, bool)]) -> bool {
+        conditions
+            .iter()
+            .all(|(fact, value)| self.get(*fact) == *value)
+    }
+
+    fn apply(self, effects: &[(Fact, bool)]) -> WorldState {
+        effects
+            .iter()
+            .fold(self, |state, (fact, value)| state.with(*fact, *value))
+    }
+}
+
+#[derive(Copy, Clone, Debug, Display, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum GoapAction {
+    Eat,
+    LookAround,
+    MoveToFood,
+    Forage,
+    SeekTrade,
+    Trade,
+    SeekMate,
+    Mate,
+}
+
+pub const ALL_ACTIONS: [GoapAction; 8] = [
+    GoapAction::Eat,
+    GoapAction::LookAround,
+    GoapAction::MoveToFood,
+    GoapAction::Forage,
+    GoapAction::SeekTrade,
+    GoapAction::Trade,
+    GoapAction::SeekMate,
+    GoapAction::Mate,
+];
+
+impl GoapAction {
+    pub fn preconditions(&self) -> &'static [(Fact, bool)] {
+        match self {
+            GoapAction::Eat => &[(Fact::HasFood, true)],
+            GoapAction::LookAround => &[(Fact::KnowsFood, false)],
+            GoapAction::MoveToFood => &[(Fact::KnowsFood, true), (Fact::AtFood, false)],
+            GoapAction::Forage => &[(Fact::AtFood, true)],
+            GoapAction::SeekTrade => &[(Fact::Balanced, false), (Fact::NearTrader, false)],
+            GoapAction::Trade => &[(Fact::Balanced, false), (Fact::NearTrader, true)],
+            GoapAction::SeekMate => &[(Fact::ReadyForBaby, true), (Fact::NearMate, false)],
+            GoapAction::Mate => &[(Fact::ReadyForBaby, true), (Fact::NearMate, true)],
+        }
+    }
+
+    pub fn effects(&self) -> &'static [(Fact, bool)] {
+        match self {
+            GoapAction::Eat => &[(Fact::Fed, true)],
+            GoapAction::LookAround => &[(Fact::KnowsFood, true)],
+            GoapAction::MoveToFood => &[(Fact::AtFood, true)],
+            GoapAction::Forage => &[(Fact::HasFood, true), (Fact::Wealthy, true)],
+            GoapAction::SeekTrade => &[(Fact::NearTrader, true)],
+            // trades can be refused, so nobody counts on getting food from one, fruit that was
+            // traded shows in the holdings when the next plan is made
+            GoapAction::Trade => &[(Fact::Balanced, true)],
+            GoapAction::SeekMate => &[(Fact::NearMate, true)],
+            GoapAction::Mate => &[(Fact::Reproduced, true)],
+        }
+    }
+
+    pub fn cost(&self) -> u32 {
+        match self {
+            GoapAction::MoveToFood | GoapAction::SeekTrade | GoapAction::SeekMate => 3,
+            _ => 1,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Display, PartialEq, Eq)]
+pub enum Goal {
+    NotStarving,
+    Reproduce,
+    AccumulateWealth,
+}
+
+impl Goal {
+    pub fn desired(&self) -> &'static [(Fact, bool)] {
+        match self {
+            Goal::NotStarving => &[(Fact::Fed, true)],
+            Goal::Reproduce => &[(Fact::Reproduced, true)],
+            Goal::AccumulateWealth => &[(Fact::Wealthy, true), (Fact::Balanced, true)],
+        }
+    }
+}
+
+/// Cheapest sequence of actions leading from the state to the goal, `None` when the goal can
+/// not be reached with at most `max_length` actions
+pub fn plan(
+    start: WorldState,
+    goal: &[(Fact, bool)],
+    actions: &[GoapAction],
+    max_length: usize,
+) -> Option<Vec<GoapAction>> {
+    let mut open = BinaryHeap::from([Reverse((0, start, Vec::new()))]);
+    let mut visited = HashSet::new();
+    while let Some(Reverse((cost, state, steps))) = open.pop() {
+        if state.satisfies(goal) {
+            return Some(steps);
+        }
+        if !visited.insert(state) || steps.len() >= max_length {
+            continue;
+        }
+        for action in actions {
+            if !state.satisfies(action.preconditions()) {
+                continue;
+            }
+            let next = state.apply(action.effects());
+            if !visited.contains(&next) {
+                let mut next_steps = steps.clone();
+                next_steps.push(*action);
+                open.push(Reverse((cost + action.cost(), next, next_steps)));
+            }
+        }
+    }
+    None
+}
+
+/// Marks people who plan their actions instead of having a big_brain thinker
+#[derive(Component)]
+pub struct GoapAgent;
+
+/// Goal a person currently pursues and the actions planned to reach it
+#[derive(Component, Default, Debug)]
+pub struct Plan {
+    pub goal: Option<Goal>,
+    pub actions: Vec<GoapAction>,
+    /// Food source the person is on their way to, they forage there once they arrive
+    pub forage_at: Option<VirtualCoords>,
+}
+
+type Agent<'a> = (
+    Entity,
+    (
+        &'a Hunger,
+        &'a FoodAmount,
+        &'a mut Knowledge,
+        &'a mut Plan,
+        &'a mut Decisions,
+    ),
+    (&'a VirtualCoords, &'a Genome, &'a Age),
+    (
+        Option<&'a Female>,
+        Option<&'a Fertile>,
+        Option<&'a Pregnant>,
+        Option<&'a BirthCooldown>,
+        Option<&'a Partners>,
+    ),
+    (
+        Option<&'a TribeMember>,
+        Option<&'a Guardian>,
+        Option<&'a MoveTo>,
+        Option<&'a Child>,
+        Option<&'a Old>,
+    ),
+);
+
+/// Every person plans the way to their most urgent goal again and starts its first step, so
+/// plans follow whatever happened in the world since the last tick. Eating is done separately
+/// in `goap_eat_system`.
+#[measured]
+#[allow(clippy::too_many_arguments)]
+pub fn goap_planning_system(
+    mut commands: Commands,
+    mut agents: Query<Agent, With<GoapAgent>>,
+    holdings: Query<&FoodAmount, With<Person>>,
+    food: Query<&FoodAmount, With<FoodSource>>,
+    sources: Query<&FoodSource>,
+    candidates: MateCandidates,
+    people_lookup: Res<Lookup<Person>>,
+    food_lookup: Res<Lookup<FoodSource>>,
+    mut vision: ResMut<VisionCache>,
+    territory: Res<Territory>,
+    time: Res<TotalTicks>,
+    config: Res<Config>,
+) {
+    let grid = Grid::from_config(&config);
+    let game = &config.game;
+    let mut sight = Sight {
+        vision: &mut vision,
+        config: &config,
+        people_lookup: &people_lookup,
+        food_lookup: &food_lookup,
+    };
+    for (
+        actor,
+        (hunger, person_food, mut knowledge, mut current, mut decisions),
+        (coords, genome, age),
+        (female, fertile, pregnant, cooldown, partners),
+        (member, guardian, moving, child, old),
+    ) in agents.iter_mut()
+    {
+        current.forage_at = forage_on_arrival(
+            &mut commands,
+            actor,
+            current.forage_at,
+            coords,
+            moving.is_some(),
+            &people_lookup,
+            &config,
+        );
+        let origin = coords.to_real(&config);
+        let imbalance = trade_imbalance(person_food);
+        let neighbours = grid
+            .neighbours(origin)
+            .into_iter()
+            .filter_map(|tile| people_lookup.entities.get(&tile).copied())
+            .collect::<Vec<_>>();
+        let trade_weight_of = |other: Entity| {
+            trade_weight(
+                imbalance?,
+                holdings.get(other).ok().and_then(trade_imbalance),
+            )
+        };
+        let mate_weight_of =
+            |other: Entity| mate_weight(&candidates, other, female.is_some(), partners, &config);
+        let hungry = hunger.apple > 1.0 || hunger.orange > 1.0;
+        let at_food = at_wanted_food(
+            hunger,
+            person_food,
+            origin,
+            member,
+            &territory,
+            &food_lookup,
+            &food,
+            &sources,
+            &config,
+        );
+        let half_goal =
+            stockpile_goal(config.ai.food_amount_goal.value, coords, time.0, &config) / 2;
+        let can_conceive = fertile.is_some() && pregnant.is_none() && cooldown.is_none();
+        let state = WorldState::default()
+            .with(Fact::Fed, !hungry)
+            .with(
+                Fact::HasFood,
+                (hunger.apple > 1.0 && person_food.apples > 0)
+                    || (hunger.orange > 1.0 && person_food.oranges > 0),
+            )
+            .with(Fact::KnowsFood, !knowledge.infos.is_empty())
+            .with(Fact::AtFood, at_food)
+            .with(
+                Fact::Wealthy,
+                person_food.apples >= half_goal && person_food.oranges >= half_goal,
+            )
+            .with(
+                Fact::Balanced,
+                !game.trade_allowed.value
+                    || imbalance.is_none_or(|i| i.abs() < config.ai.trade_need_ratio.value.ln()),
+            )
+            .with(
+                Fact::NearTrader,
+                neighbours.iter().any(|n| trade_weight_of(*n).is_some()),
+            )
+            .with(
+                Fact::ReadyForBaby,
+                can_conceive
+                    && person_food.apples.min(person_food.oranges) * 2 > game.food_for_baby.value,
+            )
+            .with(
+                Fact::NearMate,
+                neighbours.iter().any(|n| mate_weight_of(*n).is_some()),
+            );
+
+        // children stay with their guardian and people on their way only stop to eat
+        let actions: &[GoapAction] = if guardian.is_some() || moving.is_some() {
+            &[GoapAction::Eat]
+        } else {
+            &ALL_ACTIONS
+        };
+        let mut goals = vec![
+            (Goal::NotStarving, if hungry { 1.0 } else { 0.0 }),
+            (
+                Goal::Reproduce,
+                if can_conceive {
+                    mating_desire(person_food, age, female, &config)
+                } else {
+                    0.0
+                },
+            ),
+            (
+                Goal::AccumulateWealth,
+                1.0 - (person_food.apples.min(person_food.oranges) as f32
+                    / half_goal.max(1) as f32)
+                    .min(1.0),
+            ),
+        ];
+        decisions.scores = goals
+            .iter()
+            .map(|(goal, priority)| (goal.to_string(), *priority))
+            .collect();
+        goals.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let chosen = goals
+            .into_iter()
+            .filter(|(goal, priority)| *priority > 0.0 && !state.satisfies(goal.desired()))
+            .find_map(|(goal, _)| {
+                plan(
+                    state,
+                    goal.desired(),
+                    actions,
+                    config.ai.max_plan_length.value as usize,
+                )
+                .map(|steps| (goal, steps))
+            });
+        let Some((goal, steps)) = chosen else {
+            if moving.is_none() {
+                *current = Plan::default();
+            }
+            continue;
+        };
+        debug!("{} plans {:?} to reach {}", actor.index(), steps, goal);
+        let range = LifeStage::of(child, old).vision(genome, None, &config);
+        if let Some(step) = steps.first() {
+            // the same step is started again every tick until the world changes
+            if current.actions.first() != Some(step) {
+                decisions.act(time.0, step);
+            }
+        }
+        match steps.first() {
+            Some(GoapAction::LookAround) => {
+                let seen = find_food(
+                    sight.vision,
+                    &food_lookup,
+                    &food,
+                    &config,
+                    coords,
+                    range,
+                    time.0,
+                );
+                remember(&mut knowledge, seen, &config);
+                if knowledge.infos.is_empty() {
+                    // nothing in sight, look somewhere else
+                    commands
+                        .entity(actor)
+                        .insert(MoveTo::new(random_step(coords)));
+                }
+            }
+            Some(GoapAction::MoveToFood) => {
+                decisions.destinations = food_candidates(
+                    &knowledge,
+                    person_food,
+                    genome,
+                    coords,
+                    member,
+                    None,
+                    &territory,
+                    time.0,
+                    &config,
+                );
+                let destination = best_destination(&decisions.destinations)
+                    .unwrap_or_else(|| random_step(coords));
+                commands.entity(actor).insert(MoveTo::new(destination));
+                current.forage_at = Some(destination);
+            }
+            Some(GoapAction::Forage) => {
+                commands.entity(actor).insert(Forage);
+            }
+            Some(GoapAction::SeekTrade) => {
+                let destination = sight
+                    .approach_best(coords, range, trade_weight_of)
+                    .unwrap_or_else(|| random_step(coords));
+                commands.entity(actor).insert(MoveTo::new(destination));
+            }
+            Some(GoapAction::SeekMate) => {
+                let destination = sight
+                    .approach_best(coords, range, mate_weight_of)
+                    .unwrap_or_else(|| random_step(coords));
+                commands.entity(actor).insert(MoveTo::new(destination));
+            }
+            // eating is done in goap_eat_system, trading and mating happen with neighbours
+            Some(GoapAction::Eat | GoapAction::Trade | GoapAction::Mate) | None => {}
+        }
+        current.goal = Some(goal);
+        current.actions = steps;
+    }
+}
+
+#[measured]
+pub fn goap_eat_system(
+    mut agents: Query<(&Plan, &mut Hunger, &mut FoodAmount), With<GoapAgent>>,
+    config: Res<Config>,
+) {
+    for (plan, mut hunger, mut food) in agents.iter_mut() {
+        if plan.actions.first() == Some(&GoapAction::Eat) {
+            eat(&mut hunger, &mut food, &config);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::people::{foraging_system, move_system};
+    use crate::logic::testing::{world_with_known_tree, TREE};
+
+    fn hungry() -> WorldState {
+        WorldState::default().with(Fact::Balanced, true)
+    }
+
+    #[test]
+    fn hungry_person_with_food_should_eat() {
+        let state = hungry().with(Fact::HasFood, true);
+        assert_eq!(
+            plan(state, Goal::NotStarving.desired(), &ALL_ACTIONS, 5),
+            Some(vec![GoapAction::Eat])
+        );
+    }
+
+    #[test]
+    fn hungry_person_should_look_for_food_first() {
+        assert_eq!(
+            plan(hungry(), Goal::NotStarving.desired(), &ALL_ACTIONS, 5),
+            Some(vec![
+                GoapAction::LookAround,
+                GoapAction::MoveToFood,
+                GoapAction::Forage,
+                GoapAction::Eat
+            ])
+        );
+        assert_eq!(
+            plan(hungry(), Goal::NotStarving.desired(), &ALL_ACTIONS, 3),
+            None
+        );
+    }
+
+    #[test]
+    fn person_lacking_a_fruit_should_not_count_on_trade_for_food() {
+        let state = hungry()
+            .with(Fact::Balanced, false)
+            .with(Fact::NearTrader, true)
+            .with(Fact::KnowsFood, true);
+        assert_eq!(
+            plan(state, Goal::NotStarving.desired(), &ALL_ACTIONS, 5),
+            Some(vec![
+                GoapAction::MoveToFood,
+                GoapAction::Forage,
+                GoapAction::Eat
+            ])
+        );
+        let wealthy = state.with(Fact::Wealthy, true);
+        assert_eq!(
+            plan(wealthy, Goal::AccumulateWealth.desired(), &ALL_ACTIONS, 5),
+            Some(vec![GoapAction::Trade])
+        );
+    }
+
+    #[test]
+    fn reproduction_should_need_food_for_baby() {
+        assert_eq!(
+            plan(hungry(), Goal::Reproduce.desired(), &ALL_ACTIONS, 5),
+            None
+        );
+        let ready = hungry().with(Fact::ReadyForBaby, true);
+        assert_eq!(
+            plan(ready, Goal::Reproduce.desired(), &ALL_ACTIONS, 5),
+            Some(vec![GoapAction::SeekMate, GoapAction::Mate])
+        );
+    }
+
+    #[test]
+    fn facts_should_be_independent() {
+        let state = WorldState::default()
+            .with(Fact::Fed, true)
+            .with(Fact::Reproduced, true)
+            .with(Fact::Fed, false);
+        assert!(!state.get(Fact::Fed));
+        assert!(state.get(Fact::Reproduced));
+        assert!(state.satisfies(&[(Fact::Reproduced, true), (Fact::NearMate, false)]));
+    }
+
+    #[test]
+    fn planned_move_should_forage_only_at_destination() {
+        let (mut world, person) = world_with_known_tree();
+        world.entity_mut(person).insert((
+            GoapAgent,
+            Plan::default(),
+            Hunger {
+                apple: 2.0,
+                orange: 0.0,
+            },
+            Age(30),
+        ));
+        let mut schedule = Schedule::new();
+        schedule.add_systems(
+            (
+                goap_planning_system,
+                apply_system_buffers,
+                move_system,
+                apply_system_buffers,
+                foraging_system,
+                apply_system_buffers,
+            )
+                .chain(),
+        );
+        schedule.run(&mut world);
+        let plan = world.get::<Plan>(person).unwrap();
+        assert_eq!(plan.actions.first(), Some(&GoapAction::MoveToFood));
+        assert_eq!(plan.forage_at, Some(TREE));
+        for _ in 0..10 {
+            if *world.get::<VirtualCoords>(person).unwrap() == TREE {
+                break;
+            }
+            assert!(world.get::<Forage>(person).is_none());
+            assert_eq!(world.get::<FoodAmount>(person).unwrap().apples, 0);
+            schedule.run(&mut world);
+        }
+        assert_eq!(*world.get::<VirtualCoords>(person).unwrap(), TREE);
+        schedule.run(&mut world);
+        assert_eq!(world.get::<FoodAmount>(person).unwrap().apples, 1);
+        assert_eq!(world.get::<Plan>(person).unwrap().forage_at, None);
+    }
+}
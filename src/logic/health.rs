@@ -0,0 +1,61 @@
+use crate::debug::components::Performance;
+use bevy::prelude::*;
+use macros::measured;
+
+use crate::config::Config;
+use crate::logic::components::{Age, Hunger, Person};
+use crate::logic::people::{mark_entity_as_dead, CauseOfDeath};
+
+/// 1.0 is a healthy person, 0.0 is dead. Health goes down while starving and recovers when fed.
+#[derive(Component)]
+pub struct Health(pub f32);
+
+impl Default for Health {
+    fn default() -> Self {
+        Health(1.0)
+    }
+}
+
+impl Health {
+    /// Multiplier of movement speed and fertility, weak people slow down proportionally to health
+    pub fn strength(&self, config: &Config) -> f32 {
+        let threshold = config.game.weakness_threshold.value;
+        if threshold <= 0.0 || self.0 >= threshold {
+            1.0
+        } else {
+            (self.0 / threshold).max(0.0)
+        }
+    }
+
+    pub fn is_weak(&self, config: &Config) -> bool {
+        self.0 < config.game.weakness_threshold.value
+    }
+}
+
+pub fn is_starving(hunger: &Hunger) -> bool {
+    hunger.apple > 1.0 || hunger.orange > 1.0
+}
+
+#[measured]
+pub fn health_system(
+    mut commands: Commands,
+    mut people: Query<(Entity, &mut Health, &Hunger, &Age), With<Person>>,
+    config: Res<Config>,
+) {
+    for (person, mut health, hunger, age) in people.iter_mut() {
+        if is_starving(hunger) {
+            health.0 -= config.game.starvation_damage.value;
+        } else {
+            health.0 = (health.0 + config.game.health_recovery.value).min(1.0);
+        }
+        if health.0 <= 0.0 {
+            let cause = if hunger.apple > hunger.orange {
+                CauseOfDeath::AppleStarvation
+            } else {
+                CauseOfDeath::OrangeStarvation
+            };
+            info!("Person {} has died of {:?}", person.index(), cause);
+            mark_entity_as_dead(person, cause, age, &mut commands, &config);
+        }
+    }
+}
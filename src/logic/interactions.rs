@@ -4,13 +4,22 @@ use bevy::prelude::*;
 use macros::measured;
 use rand::random;
 
+use std::collections::HashSet;
+
 use crate::config::Config;
+use crate::logic::abilities::LifeStage;
 use crate::logic::components::Lookup;
+use crate::logic::genetics::Genome;
+use crate::logic::health::Health;
+use crate::logic::knowledge::{Knowledge, PriceObservation};
+use crate::logic::lineage::FamilyName;
 use crate::logic::measures::VirtualCoords;
-use crate::logic::people::{
-    free_neighbouring_coords, occupied_neighbouring_coords, Female, Fertile, Male, Person,
-    PersonBundle,
-};
+use crate::logic::partnership::Partners;
+use crate::logic::people::{occupied_neighbouring_coords, Child, Female, Fertile, Old, Person};
+use crate::logic::planet::TotalTicks;
+use crate::logic::pregnancy::{BirthCooldown, Pregnant};
+use crate::logic::relationships::Relationships;
+use crate::logic::tribes::TribeMember;
 use crate::stats::components::{Statistics, Transaction};
 
 use super::planet::FoodAmount;
@@ -44,100 +53,182 @@ pub fn add_interaction_system(
 }
 
 #[measured]
+#[allow(clippy::type_complexity)]
 pub fn breeding_interaction_system(
     mut commands: Commands,
-    mut mothers: Query<(&mut FoodAmount, &VirtualCoords, &Fertile), With<Female>>,
-    mut fathers: Query<(&mut FoodAmount, &VirtualCoords, &Fertile), Without<Female>>,
+    mut mothers: Query<
+        (Entity, &mut FoodAmount, &Health, &Genome, &Partners),
+        (
+            With<Female>,
+            With<Fertile>,
+            Without<Pregnant>,
+            Without<BirthCooldown>,
+        ),
+    >,
+    mut fathers: Query<
+        (Entity, &mut FoodAmount, &Health, &Genome, &FamilyName),
+        (Without<Female>, With<Fertile>),
+    >,
     config: Res<Config>,
-    mut lookup: ResMut<Lookup<Person>>,
     interactions: Query<&PeopleInteraction>,
+    mut stats: ResMut<Statistics>,
 ) {
+    let mut conceived = HashSet::new();
     for interaction in interactions.iter() {
-        {
-            let father = fathers.get_mut(interaction.a);
-            let mother = mothers.get_mut(interaction.b);
-            create_baby(&mut commands, &config, &mut lookup, father, mother);
-        }
-        {
-            let father = fathers.get_mut(interaction.b);
-            let mother = mothers.get_mut(interaction.a);
-            create_baby(&mut commands, &config, &mut lookup, father, mother);
+        for (father, mother) in [
+            (interaction.a, interaction.b),
+            (interaction.b, interaction.a),
+        ] {
+            if conceived.contains(&mother) {
+                continue;
+            }
+            if conceive(
+                &mut commands,
+                &config,
+                fathers.get_mut(father),
+                mothers.get_mut(mother),
+            ) {
+                conceived.insert(mother);
+            }
         }
     }
+    stats.conceptions_history.push(conceived.len() as u32);
 }
 
-fn create_baby(
+/// Father gives half of his food to the mother so she can feed the baby after it is born
+#[allow(clippy::type_complexity)]
+fn conceive(
     commands: &mut Commands,
     config: &Res<Config>,
-    lookup: &mut ResMut<Lookup<Person>>,
-    father: Result<(Mut<FoodAmount>, &VirtualCoords, &Fertile), QueryEntityError>,
-    mother: Result<(Mut<FoodAmount>, &VirtualCoords, &Fertile), QueryEntityError>,
-) {
-    if let (Ok((mut father_food, _, _)), Ok((mut mother_food, mother_coords, _))) = (father, mother)
+    father: Result<(Entity, Mut<FoodAmount>, &Health, &Genome, &FamilyName), QueryEntityError>,
+    mother: Result<(Entity, Mut<FoodAmount>, &Health, &Genome, &Partners), QueryEntityError>,
+) -> bool {
+    if let (
+        Ok((father, mut father_food, father_health, father_genome, family_name)),
+        Ok((mother, mut mother_food, mother_health, mother_genome, partners)),
+    ) = (father, mother)
     {
-        let free_space = free_neighbouring_coords(config, mother_coords, lookup);
-        if !free_space.is_empty()
+        if (partners.contains(father) || !config.social.reproduce_only_with_partner.value)
             && father_food.apples + mother_food.apples > config.game.food_for_baby.value
             && father_food.oranges + mother_food.oranges > config.game.food_for_baby.value
+            && random::<f32>()
+                < config.game.conception_probability.value
+                    * father_genome.fertility
+                    * mother_genome.fertility
+                    * father_health.strength(config)
+                    * mother_health.strength(config)
         {
-            let baby_oranges = father_food.oranges / 2 + mother_food.oranges / 2;
-            let baby_apples = father_food.apples / 2 + mother_food.apples / 2;
-            father_food.apples -= father_food.apples / 2;
-            father_food.oranges -= father_food.oranges / 2;
-            mother_food.apples -= mother_food.apples / 2;
-            mother_food.oranges -= mother_food.oranges / 2;
-            let baby_coords = free_space[random::<usize>() % free_space.len()];
-            let mut baby = commands.spawn(PersonBundle {
-                food: FoodAmount {
-                    apples: baby_apples,
-                    oranges: baby_oranges,
-                },
-                position: baby_coords,
-                ..Default::default()
-            });
-            if random::<u8>() % 2 == 0 {
-                baby.insert(Male);
+            let apples = father_food.apples / 2;
+            let oranges = father_food.oranges / 2;
+            father_food.apples -= apples;
+            father_food.oranges -= oranges;
+            mother_food.apples += apples;
+            mother_food.oranges += oranges;
+            let babies = if random::<f32>() < config.game.twins_probability.value {
+                2
             } else {
-                baby.insert(Female);
-            }
-            lookup
-                .entities
-                .insert(baby_coords.to_real(config), baby.id());
+                1
+            };
+            commands.entity(mother).insert(Pregnant {
+                father,
+                father_genome: father_genome.clone(),
+                family_name: family_name.clone(),
+                ticks_left: config.game.gestation_length.value,
+                babies,
+            });
+            debug!(
+                "{} got pregnant with {} expecting {} babies",
+                mother.index(),
+                father.index(),
+                babies
+            );
+            return true;
         }
     }
+    false
 }
 
 #[measured]
+#[allow(clippy::too_many_arguments)]
 pub fn trade_interaction_system(
     query: Query<&PeopleInteraction>,
-    people: Query<(&Person, &mut FoodAmount)>,
+    mut people: Query<(&Person, &mut FoodAmount, Option<&Child>, Option<&Old>)>,
     mut stats: ResMut<Statistics>,
+    mut relationships: ResMut<Relationships>,
+    members: Query<&TribeMember>,
+    mut knowledge: Query<&mut Knowledge>,
+    time: Res<TotalTicks>,
     config: Res<Config>,
 ) {
     if !config.game.trade_allowed.value {
         return;
     }
-    for interaction in query.iter() {
-        let (a, b) = (people.get(interaction.a), people.get(interaction.b));
-        if let (Ok((_, a_food)), Ok((_, b_food))) = (a, b) {
-            // mrs < 1 means agent a is poor in oranges and rich in apples
-            // mrs > 1 means agent a is rich in oranges and poor in apples
-            let mrs_a = calculate_marginal_rate_of_substitution(a_food.apples, a_food.oranges);
-            let mrs_b = calculate_marginal_rate_of_substitution(b_food.apples, b_food.oranges);
-            if mrs_a < 1.0 && mrs_b > 1.0 {
-                trade_apples_for_oranges(a_food, b_food, &mut stats);
-            } else if mrs_a > 1.0 && mrs_b < 1.0 {
-                trade_apples_for_oranges(b_food, a_food, &mut stats);
+    // everybody trades at most once a tick, fellow tribesmen and then those trusted the most
+    // get the chance first
+    let preference = |interaction: &PeopleInteraction| {
+        let same_tribe = matches!(
+            (members.get(interaction.a), members.get(interaction.b)),
+            (Ok(a), Ok(b)) if a == b
+        );
+        relationships.trust(interaction.a, interaction.b) + if same_tribe { 1.0 } else { 0.0 }
+    };
+    let mut interactions = query.iter().collect::<Vec<_>>();
+    interactions.sort_by(|x, y| preference(y).total_cmp(&preference(x)));
+    let mut traded = HashSet::new();
+    for interaction in interactions {
+        if traded.contains(&interaction.a) || traded.contains(&interaction.b) {
+            continue;
+        }
+        let Ok([(_, mut a_food, a_child, a_old), (_, mut b_food, b_child, b_old)]) =
+            people.get_many_mut([interaction.a, interaction.b])
+        else {
+            continue;
+        };
+        // both sides have to notice the opportunity, children are usually not skilled enough
+        let skill = LifeStage::of(a_child, a_old).trading_skill(&config)
+            * LifeStage::of(b_child, b_old).trading_skill(&config);
+        if random::<f32>() >= skill {
+            continue;
+        }
+        // mrs < 1 means agent a is poor in oranges and rich in apples
+        // mrs > 1 means agent a is rich in oranges and poor in apples
+        let mrs_a = calculate_marginal_rate_of_substitution(a_food.apples, a_food.oranges);
+        let mrs_b = calculate_marginal_rate_of_substitution(b_food.apples, b_food.oranges);
+        let price = if mrs_a < 1.0 && mrs_b > 1.0 {
+            trade_apples_for_oranges(&mut a_food, &mut b_food, &mut stats)
+        } else if mrs_a > 1.0 && mrs_b < 1.0 {
+            trade_apples_for_oranges(&mut b_food, &mut a_food, &mut stats)
+        } else {
+            None
+        };
+        if let Some(oranges_per_apple) = price {
+            traded.insert(interaction.a);
+            traded.insert(interaction.b);
+            relationships.record_trade(
+                interaction.a,
+                interaction.b,
+                config.social.trust_per_trade.value,
+            );
+            let observation = PriceObservation {
+                oranges_per_apple,
+                observed: time.0,
+            };
+            for person in [interaction.a, interaction.b] {
+                if let Ok(mut knowledge) = knowledge.get_mut(person) {
+                    knowledge.last_price = Some(observation);
+                }
             }
         }
     }
 }
 
+/// A gives apples to B for oranges when both end up better off, returns the price in oranges per
+/// apple of an accepted trade
 fn trade_apples_for_oranges(
-    a_food: &FoodAmount,
-    b_food: &FoodAmount,
+    a_food: &mut FoodAmount,
+    b_food: &mut FoodAmount,
     stats: &mut ResMut<Statistics>,
-) {
+) -> Option<f32> {
     let u_a = calculate_utility(a_food.apples, a_food.oranges);
     let u_b = calculate_utility(b_food.apples, b_food.oranges);
     let apples_to_trade = if a_food.apples > b_food.apples {
@@ -150,29 +241,30 @@ fn trade_apples_for_oranges(
     } else {
         1
     };
-    if calculate_utility(
-        a_food.apples - apples_to_trade,
-        a_food.oranges + oranges_to_trade,
-    ) > u_a
-        && calculate_utility(
-            b_food.apples + apples_to_trade,
-            b_food.oranges - oranges_to_trade,
-        ) > u_b
+    if apples_to_trade > a_food.apples || oranges_to_trade > b_food.oranges {
+        return None;
+    }
+    let a_after = FoodAmount {
+        apples: a_food.apples - apples_to_trade,
+        oranges: a_food.oranges + oranges_to_trade,
+    };
+    let b_after = FoodAmount {
+        apples: b_food.apples + apples_to_trade,
+        oranges: b_food.oranges - oranges_to_trade,
+    };
+    if calculate_utility(a_after.apples, a_after.oranges) > u_a
+        && calculate_utility(b_after.apples, b_after.oranges) > u_b
     {
         debug!(
             "Trade accepted for {} o/a, A: {:?} -> {:?}, B: {:?} -> {:?}",
             oranges_to_trade as f32 / apples_to_trade as f32,
             a_food,
-            FoodAmount {
-                apples: a_food.apples - apples_to_trade,
-                oranges: a_food.oranges + oranges_to_trade,
-            },
+            a_after,
             b_food,
-            FoodAmount {
-                apples: b_food.apples + apples_to_trade,
-                oranges: b_food.oranges - oranges_to_trade,
-            }
+            b_after
         );
+        *a_food = a_after;
+        *b_food = b_after;
         if let Some(transactions) = stats.trade_history.last_mut() {
             transactions.push(Transaction {
                 apples: apples_to_trade,
@@ -184,10 +276,12 @@ fn trade_apples_for_oranges(
                 oranges: oranges_to_trade,
             }]);
         }
+        return Some(oranges_to_trade as f32 / apples_to_trade as f32);
     }
+    None
 }
 
-fn calculate_utility(apples: u32, oranges: u32) -> f32 {
+pub(crate) fn calculate_utility(apples: u32, oranges: u32) -> f32 {
     ((apples as f32) * (oranges as f32)).sqrt()
 }
 
@@ -199,7 +293,7 @@ fn calculate_orange_marginal_utility(apples: u32, oranges: u32) -> f32 {
     calculate_utility(apples, oranges + 1) - calculate_utility(apples, oranges)
 }
 
-fn calculate_marginal_rate_of_substitution(apples: u32, oranges: u32) -> f32 {
+pub fn calculate_marginal_rate_of_substitution(apples: u32, oranges: u32) -> f32 {
     calculate_apple_marginal_utility(apples, oranges)
         / calculate_orange_marginal_utility(apples, oranges)
 }
@@ -213,3 +307,90 @@ pub fn cleanup_interactions_system(
         commands.entity(entity).despawn();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::testing;
+
+    fn person(world: &mut World, apples: u32, oranges: u32) -> Entity {
+        world.spawn((Person, FoodAmount { apples, oranges })).id()
+    }
+
+    /// One person rich in apples between two people rich in oranges
+    fn world() -> (World, Entity, Entity, Entity) {
+        let mut world = testing::world();
+        world.resource_mut::<Config>().game.trade_allowed.value = true;
+        let stranger = person(&mut world, 0, 10);
+        let trader = person(&mut world, 10, 0);
+        let partner = person(&mut world, 0, 10);
+        for (a, b) in [(trader, stranger), (trader, partner)] {
+            world.spawn(PeopleInteraction { a, b });
+            world.spawn(PeopleInteraction { a: b, b: a });
+        }
+        world.insert_resource(Statistics::default());
+        world.insert_resource(Relationships::default());
+        (world, trader, partner, stranger)
+    }
+
+    fn food(world: &World, person: Entity) -> (u32, u32) {
+        let food = world.get::<FoodAmount>(person).unwrap();
+        (food.apples, food.oranges)
+    }
+
+    #[test]
+    fn trade_should_move_fruit() {
+        let mut a = FoodAmount {
+            apples: 10,
+            oranges: 0,
+        };
+        let mut b = FoodAmount {
+            apples: 0,
+            oranges: 10,
+        };
+        let mut world = World::new();
+        world.insert_resource(Statistics::default());
+        let mut schedule = Schedule::new();
+        schedule.add_system(move |mut stats: ResMut<Statistics>| {
+            assert_eq!(
+                trade_apples_for_oranges(&mut a, &mut b, &mut stats),
+                Some(1.0)
+            );
+            assert_eq!((a.apples, a.oranges, b.apples, b.oranges), (5, 5, 5, 5));
+            // nobody gains anything from another trade
+            assert_eq!(trade_apples_for_oranges(&mut a, &mut b, &mut stats), None);
+        });
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<Statistics>().trade_history[0].len(), 1);
+    }
+
+    #[test]
+    fn trusted_partner_should_win_over_stranger() {
+        let (mut world, trader, partner, stranger) = world();
+        world
+            .resource_mut::<Relationships>()
+            .record_trade(trader, partner, 0.5);
+        let mut schedule = Schedule::new();
+        schedule.add_system(trade_interaction_system);
+        schedule.run(&mut world);
+        assert_eq!(food(&world, trader), (5, 5));
+        assert_eq!(food(&world, partner), (5, 5));
+        assert_eq!(food(&world, stranger), (0, 10));
+    }
+
+    #[test]
+    fn fellow_tribesman_should_win_over_trusted_stranger() {
+        let (mut world, trader, partner, stranger) = world();
+        world
+            .resource_mut::<Relationships>()
+            .record_trade(trader, stranger, 0.5);
+        world.entity_mut(trader).insert(TribeMember(1));
+        world.entity_mut(partner).insert(TribeMember(1));
+        world.entity_mut(stranger).insert(TribeMember(2));
+        let mut schedule = Schedule::new();
+        schedule.add_system(trade_interaction_system);
+        schedule.run(&mut world);
+        assert_eq!(food(&world, partner), (5, 5));
+        assert_eq!(food(&world, stranger), (0, 10));
+    }
+}
@@ -0,0 +1,206 @@
+use crate::debug::components::Performance;
+use bevy::prelude::*;
+use macros::measured;
+
+use crate::config::Config;
+use crate::logic::interactions::PeopleInteraction;
+use crate::logic::measures::VirtualCoords;
+use crate::logic::planet::TotalTicks;
+use crate::stats::components::Statistics;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Information {
+    pub entity: Entity,
+    pub coords: VirtualCoords,
+    /// Tick when the food source was seen, by this person or by whoever told about it
+    pub observed: u32,
+    /// 1.0 for own observations, lower for rumours passed from mouth to mouth
+    pub reliability: f32,
+    pub apples: u32,
+    pub oranges: u32,
+}
+
+/// Oranges paid for one apple in a trade the person took part in
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PriceObservation {
+    pub oranges_per_apple: f32,
+    pub observed: u32,
+}
+
+/// Ebbinghaus forgetting curve, memories fade exponentially with their age
+pub fn retention(age: u32, memory_strength: f32) -> f32 {
+    (-(age as f32) / memory_strength.max(f32::EPSILON)).exp()
+}
+
+impl Information {
+    /// How much the person still believes this information at given time
+    pub fn confidence(&self, time: u32, memory_strength: f32) -> f32 {
+        self.reliability * retention(time.saturating_sub(self.observed), memory_strength)
+    }
+
+    /// The same information as told to someone else
+    pub fn retold(&self, reliability: f32) -> Information {
+        Information {
+            reliability: self.reliability * reliability,
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Component, Default)]
+pub struct Knowledge {
+    pub infos: Vec<Information>,
+    pub last_price: Option<PriceObservation>,
+}
+
+impl Knowledge {
+    /// Keeps the fresher of two pieces of information about the same food source and forgets
+    /// the oldest ones above capacity. Returns true when anything new was learned.
+    pub fn learn(&mut self, info: Information, capacity: usize) -> bool {
+        if let Some(known) = self.infos.iter_mut().find(|i| i.entity == info.entity) {
+            let fresher = info.observed > known.observed
+                || (info.observed == known.observed && info.reliability > known.reliability);
+            if fresher {
+                *known = info;
+            }
+            return fresher;
+        }
+        self.infos.push(info);
+        if self.infos.len() > capacity {
+            if let Some(oldest) = self
+                .infos
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, i)| i.observed)
+                .map(|(index, _)| index)
+            {
+                self.infos.swap_remove(oldest);
+            }
+        }
+        true
+    }
+
+    pub fn freshest(&self, count: usize) -> Vec<&Information> {
+        let mut infos = self.infos.iter().collect::<Vec<_>>();
+        infos.sort_by_key(|info| std::cmp::Reverse(info.observed));
+        infos.truncate(count);
+        infos
+    }
+
+    /// Drops memories that are no longer believed
+    pub fn forget(&mut self, time: u32, memory_strength: f32, min_confidence: f32) {
+        self.infos
+            .retain(|info| info.confidence(time, memory_strength) >= min_confidence);
+        if let Some(price) = self.last_price {
+            if retention(time.saturating_sub(price.observed), memory_strength) < min_confidence {
+                self.last_price = None;
+            }
+        }
+    }
+}
+
+#[measured]
+pub fn forgetting_system(
+    mut people: Query<&mut Knowledge>,
+    time: Res<TotalTicks>,
+    config: Res<Config>,
+) {
+    let social = &config.social;
+    for mut knowledge in people.iter_mut() {
+        knowledge.forget(
+            time.0,
+            social.memory_strength.value,
+            social.min_confidence.value,
+        );
+    }
+}
+
+/// Neighbours tell each other about the freshest food sources they know
+#[measured]
+pub fn gossip_system(
+    interactions: Query<&PeopleInteraction>,
+    mut knowledge: Query<&mut Knowledge>,
+    mut stats: ResMut<Statistics>,
+    config: Res<Config>,
+) {
+    let social = &config.social;
+    // interactions exist in both directions, the first person of each one is the speaker
+    let mut told = Vec::new();
+    for interaction in interactions.iter() {
+        if let Ok(speaker) = knowledge.get(interaction.a) {
+            for info in speaker.freshest(social.gossip_share_count.value as usize) {
+                let rumour = info.retold(social.gossip_reliability.value);
+                if rumour.reliability >= social.min_reliability.value {
+                    told.push((interaction.b, rumour));
+                }
+            }
+        }
+    }
+    let mut learned = 0;
+    for (listener, rumour) in told {
+        if let Ok(mut listener) = knowledge.get_mut(listener) {
+            if listener.learn(rumour, social.knowledge_capacity.value as usize) {
+                learned += 1;
+            }
+        }
+    }
+    stats.gossip_history.push(learned);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(entity: u32, observed: u32, reliability: f32) -> Information {
+        Information {
+            entity: Entity::from_raw(entity),
+            coords: VirtualCoords { x: 0, y: 0 },
+            observed,
+            reliability,
+            apples: 1,
+            oranges: 0,
+        }
+    }
+
+    #[test]
+    fn fresher_information_should_replace_older() {
+        let mut knowledge = Knowledge::default();
+        assert!(knowledge.learn(info(1, 10, 1.0), 10));
+        assert!(!knowledge.learn(info(1, 5, 1.0), 10));
+        assert!(knowledge.learn(info(1, 20, 0.5), 10));
+        assert_eq!(knowledge.infos, vec![info(1, 20, 0.5)]);
+    }
+
+    #[test]
+    fn oldest_information_should_be_forgotten_above_capacity() {
+        let mut knowledge = Knowledge::default();
+        knowledge.learn(info(1, 10, 1.0), 2);
+        knowledge.learn(info(2, 5, 1.0), 2);
+        knowledge.learn(info(3, 20, 1.0), 2);
+        let freshest = knowledge.freshest(5);
+        assert_eq!(freshest, vec![&info(3, 20, 1.0), &info(1, 10, 1.0)]);
+    }
+
+    #[test]
+    fn stale_information_should_be_forgotten() {
+        let mut knowledge = Knowledge::default();
+        knowledge.learn(info(1, 0, 1.0), 10);
+        knowledge.learn(info(2, 90, 1.0), 10);
+        knowledge.learn(info(3, 90, 0.1), 10);
+        knowledge.forget(100, 50.0, 0.5);
+        assert_eq!(knowledge.infos, vec![info(2, 90, 1.0)]);
+    }
+
+    #[quickcheck]
+    fn retention_should_fade_with_age(age: u8) -> bool {
+        let retention_now = retention(age as u32, 100.0);
+        retention_now <= 1.0 && retention(age as u32 + 1, 100.0) < retention_now
+    }
+
+    #[test]
+    fn retold_information_should_be_less_reliable() {
+        let rumour = info(1, 10, 0.5).retold(0.5);
+        assert_eq!(rumour.reliability, 0.25);
+        assert_eq!(rumour.observed, 10);
+    }
+}
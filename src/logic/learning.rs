@@ -0,0 +1,648 @@
+use crate::debug::components::Performance;
+use bevy::prelude::*;
+use macros::measured;
+use rand::seq::SliceRandom;
+use rand::{thread_rng, Rng};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fs;
+
+use crate::config::{Config, ConfigPlugin};
+use crate::logic::abilities::LifeStage;
+use crate::logic::ai::{
+    at_wanted_food, best_destination, eat, find_food, food_candidates, forage_on_arrival,
+    random_step, remember, stockpile_goal, trade_imbalance, trade_weight, Sight,
+};
+use crate::logic::childhood::Guardian;
+use crate::logic::components::{Dead, FoodAmount, FoodSource, Hunger, Lookup, Person};
+use crate::logic::decisions::Decisions;
+use crate::logic::genetics::Genome;
+use crate::logic::goap::DecisionMaking;
+use crate::logic::health::is_starving;
+use crate::logic::interactions::calculate_utility;
+use crate::logic::knowledge::Knowledge;
+use crate::logic::measures::VirtualCoords;
+use crate::logic::people::{Child, Forage, MoveTo, Old};
+use crate::logic::planet::{random_food_source, TotalTicks};
+use crate::logic::tribes::{Territory, TribeMember};
+use crate::logic::vision::VisionCache;
+use crate::logic::LogicPlugin;
+use crate::stats::components::Statistics;
+
+pub const POLICY_PATH: &str = "./data/policy.json";
+
+/// Reward for every tick a learner stays alive
+const ALIVE_REWARD: f32 = 0.1;
+/// Reward for every tick a learner is starving
+const STARVING_REWARD: f32 = -1.0;
+/// Reward when a learner dies, whatever the cause
+const DEATH_REWARD: f32 = -100.0;
+
+#[derive(Copy, Clone, Serialize, Deserialize, Debug, Display, PartialEq, Eq)]
+pub enum LearnedAction {
+    Eat,
+    Forage,
+    MoveToFood,
+    LookAround,
+    SeekTrade,
+    Wander,
+}
+
+pub const ALL_ACTIONS: [LearnedAction; 6] = [
+    LearnedAction::Eat,
+    LearnedAction::Forage,
+    LearnedAction::MoveToFood,
+    LearnedAction::LookAround,
+    LearnedAction::SeekTrade,
+    LearnedAction::Wander,
+];
+
+/// What a learner knows about their situation, coarse enough for a table of all situations
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Observation {
+    pub hungry_for_apples: bool,
+    pub hungry_for_oranges: bool,
+    pub knows_food: bool,
+    /// Standing on a tree with the fruit they want
+    pub at_food: bool,
+    /// Holdings far enough from the ratio they would like to trade for
+    pub unbalanced: bool,
+    /// 0 - no fruit, 1 - less than half of the food amount goal, 2 - at least half of it, the goal
+    /// is raised to stockpile before the growing season ends
+    pub apples: u8,
+    pub oranges: u8,
+}
+
+impl Observation {
+    pub fn new(
+        hunger: &Hunger,
+        food: &FoodAmount,
+        knowledge: &Knowledge,
+        at_food: bool,
+        coords: &VirtualCoords,
+        time: u32,
+        config: &Config,
+    ) -> Self {
+        let half_goal = stockpile_goal(config.ai.food_amount_goal.value, coords, time, config) / 2;
+        let level = |amount: u32| match amount {
+            0 => 0,
+            amount if amount < half_goal => 1,
+            _ => 2,
+        };
+        Observation {
+            hungry_for_apples: hunger.apple > 1.0,
+            hungry_for_oranges: hunger.orange > 1.0,
+            knows_food: !knowledge.infos.is_empty(),
+            at_food,
+            unbalanced: config.game.trade_allowed.value
+                && trade_imbalance(food)
+                    .is_some_and(|i| i.abs() >= config.ai.trade_need_ratio.value.ln()),
+            apples: level(food.apples),
+            oranges: level(food.oranges),
+        }
+    }
+
+    /// Key of the situation in the policy
+    pub fn index(&self) -> u16 {
+        self.hungry_for_apples as u16
+            | (self.hungry_for_oranges as u16) << 1
+            | (self.knows_food as u16) << 2
+            | (self.at_food as u16) << 3
+            | (self.unbalanced as u16) << 4
+            | (self.apples as u16) << 5
+            | (self.oranges as u16) << 7
+    }
+}
+
+/// Expected return of every action in the situations met during training
+#[derive(Resource, Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Policy {
+    /// Training episodes the values were learned in
+    pub episodes: u32,
+    pub values: BTreeMap<u16, [f32; ALL_ACTIONS.len()]>,
+}
+
+impl Policy {
+    pub fn load(path: &str) -> Result<Policy, String> {
+        let data =
+            fs::read_to_string(path).map_err(|e| format!("Unable to read {}: {}", path, e))?;
+        serde_json::from_str(&data).map_err(|e| format!("Invalid policy in {}: {}", path, e))
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let data = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, data).map_err(|e| format!("Unable to write {}: {}", path, e))
+    }
+
+    /// Values of all actions in the situation, unknown situations have no value yet
+    pub fn values(&self, observation: &Observation) -> [f32; ALL_ACTIONS.len()] {
+        self.values
+            .get(&observation.index())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Best known action, ties are broken at random. With the exploration probability any action
+    /// is tried instead.
+    pub fn choose(
+        &self,
+        observation: &Observation,
+        exploration: f32,
+        random: &mut impl Rng,
+    ) -> LearnedAction {
+        if random.gen_range(0.0..1.0) < exploration {
+            return *ALL_ACTIONS.choose(random).expect("there are actions");
+        }
+        let values = self.values(observation);
+        let best = values.iter().copied().fold(f32::MIN, f32::max);
+        let candidates = ALL_ACTIONS
+            .iter()
+            .zip(values)
+            .filter(|(_, value)| *value >= best)
+            .map(|(action, _)| *action)
+            .collect::<Vec<_>>();
+        *candidates
+            .choose(random)
+            .expect("the best action is a candidate")
+    }
+
+    /// Q-learning update of the value of an action with the reward it brought, there is no next
+    /// situation after death. The discount covers all ticks the action took.
+    pub fn learn(
+        &mut self,
+        observation: &Observation,
+        action: LearnedAction,
+        reward: f32,
+        next: Option<&Observation>,
+        rate: f32,
+        discount: f32,
+    ) {
+        let future = next
+            .map(|next| self.values(next).into_iter().fold(f32::MIN, f32::max))
+            .unwrap_or(0.0);
+        let position = ALL_ACTIONS
+            .iter()
+            .position(|a| *a == action)
+            .expect("all actions are listed");
+        let values = self.values.entry(observation.index()).or_default();
+        values[position] += rate * (reward + discount * future - values[position]);
+    }
+}
+
+/// Present while training, learners explore and update the policy only then
+#[derive(Resource)]
+pub struct Training;
+
+/// Person following the learned policy
+#[derive(Component, Default, Debug)]
+pub struct Learner {
+    /// Situation and action of the last decision, waiting for its reward
+    last: Option<(Observation, LearnedAction)>,
+    /// Reward collected since the last decision, discounted by the ticks it came after it
+    reward: f32,
+    /// Ticks since the last decision, children do not decide while they have a guardian
+    ticks: i32,
+    /// Utility of the holdings in the previous tick
+    utility: Option<f32>,
+    /// Action decided in this tick
+    acting: Option<LearnedAction>,
+    /// Food source the person is on their way to, they forage there once they arrive
+    forage_at: Option<VirtualCoords>,
+}
+
+type LearnerQuery<'a> = (
+    Entity,
+    (&'a mut Learner, &'a mut Decisions, &'a mut Knowledge),
+    (&'a Hunger, &'a FoodAmount, &'a VirtualCoords, &'a Genome),
+    (
+        Option<&'a TribeMember>,
+        Option<&'a Guardian>,
+        Option<&'a MoveTo>,
+        Option<&'a Child>,
+        Option<&'a Old>,
+    ),
+);
+
+/// Collects the rewards of every learner and lets them choose the next action. Like thinkers
+/// they decide again in every tick and a move goes on until another move replaces it. Eating is
+/// done separately in `learned_eat_system`.
+#[measured]
+#[allow(clippy::too_many_arguments)]
+pub fn learning_system(
+    mut commands: Commands,
+    mut learners: Query<LearnerQuery, With<Person>>,
+    holdings: Query<&FoodAmount, With<Person>>,
+    food: Query<&FoodAmount, With<FoodSource>>,
+    sources: Query<&FoodSource>,
+    people_lookup: Res<Lookup<Person>>,
+    food_lookup: Res<Lookup<FoodSource>>,
+    mut vision: ResMut<VisionCache>,
+    mut policy: ResMut<Policy>,
+    training: Option<Res<Training>>,
+    territory: Res<Territory>,
+    time: Res<TotalTicks>,
+    config: Res<Config>,
+) {
+    let mut random = thread_rng();
+    let mut sight = Sight {
+        vision: &mut vision,
+        config: &config,
+        people_lookup: &people_lookup,
+        food_lookup: &food_lookup,
+    };
+    let exploration = if training.is_some() {
+        config.ai.exploration.value
+    } else {
+        0.0
+    };
+    for (
+        actor,
+        (mut learner, mut decisions, mut knowledge),
+        (hunger, person_food, coords, genome),
+        (member, guardian, moving, child, old),
+    ) in learners.iter_mut()
+    {
+        // eating uses fruit up, so only gains of utility are rewarded and hunger is paid for
+        // while starving
+        let utility = calculate_utility(person_food.apples, person_food.oranges);
+        let mut reward = ALIVE_REWARD + (utility - learner.utility.unwrap_or(utility)).max(0.0);
+        if is_starving(hunger) {
+            reward += STARVING_REWARD;
+        }
+        if learner.last.is_some() {
+            learner.reward += config.ai.discount.value.powi(learner.ticks) * reward;
+            learner.ticks += 1;
+        }
+        learner.utility = Some(utility);
+        // children stay with their guardian and only eat what they have
+        if guardian.is_some() {
+            learner.acting = Some(LearnedAction::Eat);
+            continue;
+        }
+        learner.forage_at = forage_on_arrival(
+            &mut commands,
+            actor,
+            learner.forage_at,
+            coords,
+            moving.is_some(),
+            &people_lookup,
+            &config,
+        );
+        let origin = coords.to_real(&config);
+        let at_food = at_wanted_food(
+            hunger,
+            person_food,
+            origin,
+            member,
+            &territory,
+            &food_lookup,
+            &food,
+            &sources,
+            &config,
+        );
+        let observation = Observation::new(
+            hunger,
+            person_food,
+            &knowledge,
+            at_food,
+            coords,
+            time.0,
+            &config,
+        );
+        if let Some((last, action)) = learner.last {
+            if training.is_some() {
+                policy.learn(
+                    &last,
+                    action,
+                    learner.reward,
+                    Some(&observation),
+                    config.ai.learning_rate.value,
+                    config.ai.discount.value.powi(learner.ticks),
+                );
+            }
+        }
+        learner.reward = 0.0;
+        learner.ticks = 0;
+        let action = policy.choose(&observation, exploration, &mut random);
+        learner.last = Some((observation, action));
+        learner.acting = Some(action);
+        decisions.scores = ALL_ACTIONS
+            .iter()
+            .zip(policy.values(&observation))
+            .map(|(action, value)| (action.to_string(), value))
+            .collect();
+        // the same action is usually chosen again every tick until the situation changes
+        if decisions.current() != Some(action.to_string().as_str()) {
+            decisions.act(time.0, action);
+        }
+        let range = LifeStage::of(child, old).vision(genome, None, &config);
+        match action {
+            LearnedAction::MoveToFood => {
+                let seen = find_food(
+                    sight.vision,
+                    &food_lookup,
+                    &food,
+                    &config,
+                    coords,
+                    range,
+                    time.0,
+                );
+                remember(&mut knowledge, seen, &config);
+                decisions.destinations = food_candidates(
+                    &knowledge,
+                    person_food,
+                    genome,
+                    coords,
+                    member,
+                    None,
+                    &territory,
+                    time.0,
+                    &config,
+                );
+                let destination = best_destination(&decisions.destinations)
+                    .unwrap_or_else(|| random_step(coords));
+                head_for(&mut commands, actor, moving, destination);
+                learner.forage_at = Some(destination);
+            }
+            LearnedAction::LookAround => {
+                let seen = find_food(
+                    sight.vision,
+                    &food_lookup,
+                    &food,
+                    &config,
+                    coords,
+                    range,
+                    time.0,
+                );
+                remember(&mut knowledge, seen, &config);
+            }
+            LearnedAction::SeekTrade => {
+                let own = trade_imbalance(person_food);
+                let destination = sight
+                    .approach_best(coords, range, |other| {
+                        trade_weight(own?, holdings.get(other).ok().and_then(trade_imbalance))
+                    })
+                    .unwrap_or_else(|| random_step(coords));
+                head_for(&mut commands, actor, moving, destination);
+                learner.forage_at = None;
+            }
+            LearnedAction::Wander => {
+                commands
+                    .entity(actor)
+                    .insert(MoveTo::new(random_step(coords)));
+                learner.forage_at = None;
+            }
+            LearnedAction::Forage => {
+                commands.entity(actor).insert(Forage);
+            }
+            LearnedAction::Eat => {}
+        }
+    }
+}
+
+/// Starts moving to the destination, unless already on the way there with a planned path
+fn head_for(
+    commands: &mut Commands,
+    actor: Entity,
+    moving: Option<&MoveTo>,
+    destination: VirtualCoords,
+) {
+    if moving.map(|moving| moving.dest) != Some(destination) {
+        commands.entity(actor).insert(MoveTo::new(destination));
+    }
+}
+
+#[measured]
+pub fn learned_eat_system(
+    mut learners: Query<(&Learner, &mut Hunger, &mut FoodAmount), With<Person>>,
+    config: Res<Config>,
+) {
+    for (learner, mut hunger, mut food) in learners.iter_mut() {
+        if learner.acting == Some(LearnedAction::Eat) {
+            eat(&mut hunger, &mut food, &config);
+        }
+    }
+}
+
+/// The last decision of a learner who died is punished
+#[measured]
+pub fn learner_death_system(
+    mut learners: Query<&mut Learner, Added<Dead>>,
+    mut policy: ResMut<Policy>,
+    training: Option<Res<Training>>,
+    config: Res<Config>,
+) {
+    for mut learner in learners.iter_mut() {
+        if let Some((last, action)) = learner.last.take() {
+            if training.is_some() {
+                let discount = config.ai.discount.value.powi(learner.ticks);
+                policy.learn(
+                    &last,
+                    action,
+                    learner.reward + discount * DEATH_REWARD,
+                    None,
+                    config.ai.learning_rate.value,
+                    discount,
+                );
+            }
+        }
+    }
+}
+
+/// Runs the configured number of headless simulations of learners, carrying the policy from one
+/// to the next, and saves it to `POLICY_PATH`
+pub fn train() {
+    let mut policy = Policy::load(POLICY_PATH).unwrap_or_else(|e| {
+        eprintln!("{}, training a new policy", e);
+        Policy::default()
+    });
+    let mut episode = 0;
+    loop {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(Performance::new(100))
+            .add_plugin(ConfigPlugin)
+            .init_resource::<Statistics>()
+            .add_plugin(LogicPlugin)
+            .insert_resource(policy)
+            .insert_resource(Training)
+            .add_startup_system(spawn_food_sources.in_base_set(StartupSet::PostStartup));
+        let mut config = app.world.resource_mut::<Config>();
+        config.ai.decision_making.value = DecisionMaking::Learned;
+        let (episodes, length) = (
+            config.ai.training_episodes.value,
+            config.ai.episode_length.value,
+        );
+        for _ in 0..length {
+            app.update();
+        }
+        let survivors = app
+            .world
+            .query_filtered::<(), With<Person>>()
+            .iter(&app.world)
+            .count();
+        policy = app
+            .world
+            .remove_resource::<Policy>()
+            .expect("policy is kept during training");
+        policy.episodes += 1;
+        episode += 1;
+        println!(
+            "Episode {}/{}: {} people alive, {} situations learned",
+            episode,
+            episodes,
+            survivors,
+            policy.values.len()
+        );
+        if episode >= episodes {
+            break;
+        }
+    }
+    match policy.save(POLICY_PATH) {
+        Ok(()) => println!("Policy saved to {}", POLICY_PATH),
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Trees of the map without tiles to draw them on
+fn spawn_food_sources(
+    mut commands: Commands,
+    config: Res<Config>,
+    mut food_lookup: ResMut<Lookup<FoodSource>>,
+) {
+    let mut random = thread_rng();
+    for x in 0..config.map.size_x.value as i32 {
+        for y in 0..config.map.size_y.value as i32 {
+            let coords = VirtualCoords { x, y };
+            if let Some((source, amount)) = random_food_source(&coords, &config, &mut random) {
+                let entity = commands.spawn((coords, source, amount)).id();
+                food_lookup.entities.insert(coords.to_real(&config), entity);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::testing;
+    use crate::logic::GeometryType;
+
+    fn observation() -> Observation {
+        Observation {
+            hungry_for_apples: true,
+            hungry_for_oranges: false,
+            knows_food: false,
+            at_food: false,
+            unbalanced: false,
+            apples: 1,
+            oranges: 0,
+        }
+    }
+
+    #[test]
+    fn observations_should_have_distinct_indices() {
+        let mut indices = std::collections::HashSet::new();
+        for bits in 0..32u16 {
+            for apples in 0..3 {
+                for oranges in 0..3 {
+                    let observation = Observation {
+                        hungry_for_apples: bits & 1 != 0,
+                        hungry_for_oranges: bits & 2 != 0,
+                        knows_food: bits & 4 != 0,
+                        at_food: bits & 8 != 0,
+                        unbalanced: bits & 16 != 0,
+                        apples,
+                        oranges,
+                    };
+                    assert!(indices.insert(observation.index()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rewarded_action_should_become_the_best() {
+        let mut policy = Policy::default();
+        let observation = observation();
+        for _ in 0..10 {
+            policy.learn(&observation, LearnedAction::Eat, 1.0, None, 0.1, 0.9);
+            policy.learn(&observation, LearnedAction::Wander, -1.0, None, 0.1, 0.9);
+        }
+        let mut random = thread_rng();
+        assert_eq!(
+            policy.choose(&observation, 0.0, &mut random),
+            LearnedAction::Eat
+        );
+        assert!(policy.values(&observation)[0] > 0.6);
+    }
+
+    #[test]
+    fn future_value_should_be_discounted() {
+        let mut policy = Policy::default();
+        let next = Observation {
+            hungry_for_apples: false,
+            ..observation()
+        };
+        policy
+            .values
+            .insert(next.index(), [10.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        policy.learn(
+            &observation(),
+            LearnedAction::Eat,
+            0.0,
+            Some(&next),
+            0.5,
+            0.9,
+        );
+        assert!((policy.values(&observation())[0] - 4.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn learner_should_stockpile_before_the_season_ends() {
+        let mut world = testing::world();
+        let mut config = world.resource_mut::<Config>();
+        config.map.geometry.value = GeometryType::FlatEarth;
+        let observe = |config: &Config| {
+            let hunger = Hunger {
+                apple: 0.0,
+                orange: 0.0,
+            };
+            let food = FoodAmount {
+                apples: config.ai.food_amount_goal.value / 2,
+                oranges: 0,
+            };
+            // the first row is out of the growing season by then
+            let coords = VirtualCoords { x: 0, y: 0 };
+            Observation::new(
+                &hunger,
+                &food,
+                &Knowledge::default(),
+                false,
+                &coords,
+                200,
+                config,
+            )
+        };
+        assert_eq!(observe(&config).apples, 1);
+        config.ai.season_horizon.value = 0;
+        assert_eq!(observe(&config).apples, 2);
+    }
+
+    #[test]
+    fn policy_should_survive_saving() {
+        let mut policy = Policy {
+            episodes: 3,
+            ..Policy::default()
+        };
+        policy
+            .values
+            .insert(observation().index(), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let data = serde_json::to_string(&policy).unwrap();
+        assert_eq!(serde_json::from_str::<Policy>(&data).unwrap(), policy);
+    }
+}
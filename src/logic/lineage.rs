@@ -0,0 +1,190 @@
+use crate::debug::components::Performance;
+use bevy::prelude::*;
+use macros::measured;
+use std::collections::HashMap;
+
+use crate::logic::components::{Dead, Name};
+use crate::logic::people::{Age, Male, Person};
+use crate::logic::planet::TotalTicks;
+
+/// Biological parents of a person, set only on people that were born in the simulation
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Parents {
+    pub father: Entity,
+    pub mother: Entity,
+}
+
+/// Children born to this person, in order of birth
+#[derive(Component, Default, Debug)]
+pub struct Offspring(pub Vec<Entity>);
+
+#[derive(Component, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FamilyName(pub String);
+
+pub struct LineageRecord {
+    pub name: String,
+    pub family_name: String,
+    pub male: bool,
+    pub father: Option<Entity>,
+    pub mother: Option<Entity>,
+    pub children: Vec<Entity>,
+    /// Tick of birth, negative for people who were already alive when the simulation started
+    pub born: i64,
+    pub died: Option<u32>,
+}
+
+/// Archive of everyone that ever lived, kept after their entities are despawned
+#[derive(Resource, Default)]
+pub struct Genealogy {
+    pub records: HashMap<Entity, LineageRecord>,
+}
+
+impl Genealogy {
+    /// Counts all descendants of every person in the archive in a single pass.
+    /// Descendants reachable through both parents (inbreeding) are counted twice.
+    pub fn descendant_counts(&self) -> HashMap<Entity, usize> {
+        let mut counts = HashMap::with_capacity(self.records.len());
+        for person in self.records.keys() {
+            self.count_descendants(*person, &mut counts);
+        }
+        counts
+    }
+
+    fn count_descendants(&self, person: Entity, counts: &mut HashMap<Entity, usize>) -> usize {
+        if let Some(count) = counts.get(&person) {
+            return *count;
+        }
+        let mut count = 0;
+        if let Some(record) = self.records.get(&person) {
+            for child in record.children.iter() {
+                count += 1 + self.count_descendants(*child, counts);
+            }
+        }
+        counts.insert(person, count);
+        count
+    }
+}
+
+#[measured]
+#[allow(clippy::type_complexity)]
+pub fn register_births_system(
+    born: Query<
+        (
+            Entity,
+            &Name,
+            &FamilyName,
+            &Age,
+            Option<&Parents>,
+            Option<&Male>,
+        ),
+        Added<Person>,
+    >,
+    mut offspring: Query<&mut Offspring>,
+    mut genealogy: ResMut<Genealogy>,
+    time: Res<TotalTicks>,
+) {
+    for (person, name, family_name, age, parents, male) in born.iter() {
+        genealogy.records.insert(
+            person,
+            LineageRecord {
+                name: name.0.clone(),
+                family_name: family_name.0.clone(),
+                male: male.is_some(),
+                father: parents.map(|p| p.father),
+                mother: parents.map(|p| p.mother),
+                children: Vec::new(),
+                born: time.0 as i64 - age.0 as i64,
+                died: None,
+            },
+        );
+        if let Some(parents) = parents {
+            for parent in [parents.father, parents.mother] {
+                if let Ok(mut children) = offspring.get_mut(parent) {
+                    children.0.push(person);
+                }
+                if let Some(record) = genealogy.records.get_mut(&parent) {
+                    record.children.push(person);
+                }
+            }
+        }
+    }
+}
+
+#[measured]
+pub fn register_deaths_system(
+    died: Query<Entity, Added<Dead>>,
+    mut genealogy: ResMut<Genealogy>,
+    time: Res<TotalTicks>,
+) {
+    for person in died.iter() {
+        if let Some(record) = genealogy.records.get_mut(&person) {
+            record.died = Some(time.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::testing;
+
+    fn record(children: Vec<Entity>) -> LineageRecord {
+        LineageRecord {
+            name: String::from("Test"),
+            family_name: String::from("Test"),
+            male: true,
+            father: None,
+            mother: None,
+            children,
+            born: 0,
+            died: None,
+        }
+    }
+
+    #[test]
+    fn founder_should_be_born_before_the_start() {
+        let mut world = testing::world();
+        world.insert_resource(TotalTicks(5));
+        world.insert_resource(Genealogy::default());
+        let founder = world
+            .spawn((
+                Person,
+                Name(String::from("Founder")),
+                FamilyName(String::from("Test")),
+                Age(100),
+            ))
+            .id();
+        let mut schedule = Schedule::new();
+        schedule.add_systems((register_births_system, register_deaths_system));
+        schedule.run(&mut world);
+        world.entity_mut(founder).insert(Dead);
+        world.resource_mut::<TotalTicks>().0 = 20;
+        schedule.run(&mut world);
+
+        let record = &world.resource::<Genealogy>().records[&founder];
+        assert_eq!(record.born, -95);
+        assert_eq!(record.died, Some(20));
+    }
+
+    #[test]
+    fn should_count_descendants_over_generations() {
+        let grandpa = Entity::from_raw(0);
+        let father = Entity::from_raw(1);
+        let uncle = Entity::from_raw(2);
+        let child = Entity::from_raw(3);
+        let mut genealogy = Genealogy::default();
+        genealogy
+            .records
+            .insert(grandpa, record(vec![father, uncle]));
+        genealogy.records.insert(father, record(vec![child]));
+        genealogy.records.insert(uncle, record(vec![]));
+        genealogy.records.insert(child, record(vec![]));
+
+        let counts = genealogy.descendant_counts();
+
+        assert_eq!(counts[&grandpa], 3);
+        assert_eq!(counts[&father], 1);
+        assert_eq!(counts[&uncle], 0);
+        assert_eq!(counts[&child], 0);
+    }
+}
@@ -85,7 +85,7 @@ mod tests {
 
     #[quickcheck]
     fn should_limit_value_in_bounds(x: u32, delta: i32, min: u32, max: u32) -> bool {
-        if min >= max || delta < -100 || delta > 100 || x >= max || x < min || max > 100 {
+        if min >= max || !(-100..=100).contains(&delta) || x >= max || x < min || max > 100 {
             return true;
         }
         println!("x: {}, delta: {}, min: {}, max: {}", x, delta, min, max);
@@ -0,0 +1,147 @@
+use rand::random;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+use crate::config::Config;
+use crate::logic::genetics::Genome;
+use crate::logic::people::CauseOfDeath;
+
+/// How fast the infant mortality peak fades, per year of age
+const INFANT_MORTALITY_DECAY: f64 = 4.0;
+
+#[derive(Copy, Clone, Serialize, Deserialize, Debug, Display, PartialEq, Eq)]
+pub enum MortalityModel {
+    HardLimit,
+    GompertzMakeham,
+    LifeTable,
+}
+
+/// Yearly hazard rates split by what they are attributed to
+#[derive(Debug, Default, PartialEq)]
+pub struct Hazard {
+    pub aging: f64,
+    pub other: f64,
+}
+
+impl Hazard {
+    pub fn total(&self) -> f64 {
+        self.aging + self.other
+    }
+}
+
+/// Gompertz–Makeham law: constant background hazard plus an exponentially growing aging hazard
+pub fn gompertz_makeham(age_years: f64, background: f64, initial: f64, growth: f64) -> Hazard {
+    Hazard {
+        aging: initial * (growth * age_years).exp(),
+        other: background,
+    }
+}
+
+/// Hazard of a life table given as yearly probabilities of dying, the last entry is used
+/// for everyone older than the table
+pub fn life_table(age_years: f64, probabilities: &[f32]) -> Hazard {
+    let probability = probabilities
+        .get(age_years as usize)
+        .or(probabilities.last())
+        .copied()
+        .unwrap_or(0.0)
+        .clamp(0.0, 0.999_999) as f64;
+    Hazard {
+        aging: -(1.0 - probability).ln(),
+        other: 0.0,
+    }
+}
+
+pub fn infant_mortality(age_years: f64, at_birth: f64) -> f64 {
+    at_birth * (-INFANT_MORTALITY_DECAY * age_years).exp()
+}
+
+/// Converts a yearly hazard rate to the probability of dying during a single tick
+pub fn probability_per_tick(hazard: f64, year_length: u32) -> f64 {
+    1.0 - (-hazard / year_length.max(1) as f64).exp()
+}
+
+pub fn hazard(config: &Config, age: u32, genome: &Genome) -> Hazard {
+    let game = &config.game;
+    let age_years = age as f64 / game.year_length.value.max(1) as f64;
+    // longer living people age slower
+    let biological_age = age_years / genome.lifespan.max(f32::EPSILON) as f64;
+    let mut hazard = match game.mortality_model.value {
+        MortalityModel::HardLimit => Hazard::default(),
+        MortalityModel::GompertzMakeham => gompertz_makeham(
+            biological_age,
+            game.makeham_background.value as f64,
+            game.gompertz_initial.value as f64,
+            game.gompertz_growth.value as f64,
+        ),
+        MortalityModel::LifeTable => life_table(biological_age, &game.life_table.value),
+    };
+    hazard.other += infant_mortality(age_years, game.infant_mortality.value as f64);
+    hazard
+}
+
+/// Decides if the person dies of natural causes in this tick
+pub fn natural_death(config: &Config, age: u32, genome: &Genome) -> Option<CauseOfDeath> {
+    if config.game.mortality_model.value == MortalityModel::HardLimit
+        && config.game.max_person_age.value > 0
+        && age > genome.max_age(config)
+    {
+        return Some(CauseOfDeath::OldAge);
+    }
+    let hazard = hazard(config, age, genome);
+    if hazard.total() <= 0.0 {
+        return None;
+    }
+    let year_length = config.game.year_length.value;
+    if random::<f64>() < probability_per_tick(hazard.total(), year_length) {
+        if random::<f64>() * hazard.total() < hazard.aging {
+            Some(CauseOfDeath::OldAge)
+        } else {
+            Some(CauseOfDeath::Other)
+        }
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[quickcheck]
+    fn probability_per_tick_should_be_a_probability(hazard: f64, year_length: u32) -> bool {
+        if !hazard.is_finite() || hazard < 0.0 {
+            return true;
+        }
+        let probability = probability_per_tick(hazard, year_length);
+        (0.0..=1.0).contains(&probability)
+    }
+
+    #[quickcheck]
+    fn gompertz_makeham_hazard_should_grow_with_age(age: u8) -> bool {
+        let younger = gompertz_makeham(age as f64, 0.005, 0.002, 0.25);
+        let older = gompertz_makeham(age as f64 + 1.0, 0.005, 0.002, 0.25);
+        older.total() > younger.total()
+    }
+
+    #[test]
+    fn ticks_of_a_year_should_add_up_to_the_yearly_probability() {
+        let table = [0.1];
+        let hazard = life_table(0.0, &table).total();
+        let survival = (1.0 - probability_per_tick(hazard, 1000)).powi(1000);
+        assert!((1.0 - survival - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn life_table_should_use_last_entry_for_oldest_people() {
+        let table = [0.1, 0.5];
+        assert_eq!(life_table(30.0, &table), life_table(1.5, &table));
+        assert!(life_table(0.5, &table).total() < life_table(1.0, &table).total());
+    }
+
+    #[test]
+    fn infant_mortality_should_fade_with_age() {
+        assert_eq!(infant_mortality(0.0, 0.1), 0.1);
+        assert!(infant_mortality(2.0, 0.1) < 0.001);
+    }
+}
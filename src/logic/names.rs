@@ -0,0 +1,58 @@
+use rand::seq::SliceRandom;
+
+const MALE_NAMES: [&str; 20] = [
+    "Adam", "Bartek", "Czesiek", "Darek", "Edek", "Franek", "Grzesiek", "Henio", "Igor", "Janek",
+    "Kuba", "Leszek", "Marek", "Nikodem", "Olek", "Piotrek", "Radek", "Staszek", "Tomek",
+    "Zbyszek",
+];
+
+const FEMALE_NAMES: [&str; 20] = [
+    "Ala", "Basia", "Celina", "Dorota", "Ewa", "Franka", "Gosia", "Hania", "Iza", "Jola", "Kasia",
+    "Lena", "Marta", "Natalia", "Ola", "Pola", "Renata", "Sabina", "Tosia", "Zosia",
+];
+
+const FAMILY_NAMES: [&str; 24] = [
+    "Nowak",
+    "Wójcik",
+    "Kowalczyk",
+    "Mazur",
+    "Krawczyk",
+    "Zając",
+    "Król",
+    "Wróbel",
+    "Dudek",
+    "Pawlak",
+    "Walczak",
+    "Sikora",
+    "Baran",
+    "Duda",
+    "Szewczyk",
+    "Tomaszewicz",
+    "Pietrzak",
+    "Jabłoń",
+    "Gruszka",
+    "Kaczmarek",
+    "Michalak",
+    "Wieczorek",
+    "Adamczyk",
+    "Jasiński",
+];
+
+pub fn given_name(male: bool) -> String {
+    let names = if male { &MALE_NAMES } else { &FEMALE_NAMES };
+    names
+        .choose(&mut rand::thread_rng())
+        .expect("name list is not empty")
+        .to_string()
+}
+
+pub fn family_name() -> String {
+    FAMILY_NAMES
+        .choose(&mut rand::thread_rng())
+        .expect("family name list is not empty")
+        .to_string()
+}
+
+pub fn full_name(given_name: &str, family_name: &str) -> String {
+    format!("{} {}", given_name, family_name)
+}
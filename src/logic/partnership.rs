@@ -0,0 +1,243 @@
+use crate::debug::components::Performance;
+use bevy::prelude::*;
+use macros::measured;
+
+use crate::config::Config;
+use crate::logic::components::{Age, Dead, FoodAmount, Person};
+use crate::logic::genetics::Genome;
+use crate::logic::interactions::PeopleInteraction;
+use crate::logic::people::{Female, Fertile, Male};
+use crate::logic::planet::TotalTicks;
+use crate::logic::relationships::Relationships;
+use crate::stats::components::{PartnerMatch, Statistics};
+
+pub struct Partnership {
+    pub partner: Entity,
+    pub since: u32,
+}
+
+#[derive(Component, Default)]
+pub struct Partners(pub Vec<Partnership>);
+
+impl Partners {
+    pub fn contains(&self, person: Entity) -> bool {
+        self.0.iter().any(|p| p.partner == person)
+    }
+}
+
+/// How attractive the candidate is for the chooser, from 0.0 to 1.0
+fn attractiveness(
+    config: &Config,
+    chooser_age: u32,
+    candidate_age: u32,
+    candidate_food: &FoodAmount,
+    candidate_genome: &Genome,
+    trust: f32,
+) -> f32 {
+    let social = &config.social;
+    let wealth = (candidate_food.apples + candidate_food.oranges) as f32;
+    let wealth_score = wealth / (wealth + config.ai.food_amount_goal.value as f32);
+    let age_difference = (chooser_age as f32 - candidate_age as f32).abs();
+    let age_score = 1.0 - (age_difference / config.game.max_person_age.value as f32).min(1.0);
+    let traits_score =
+        (candidate_genome.fertility + (candidate_genome.lifespan / 2.0).min(1.0)) / 2.0;
+    let total_weight = social.wealth_weight.value
+        + social.age_weight.value
+        + social.traits_weight.value
+        + social.trust_weight.value;
+    if total_weight <= 0.0 {
+        return 1.0;
+    }
+    (social.wealth_weight.value * wealth_score
+        + social.age_weight.value * age_score
+        + social.traits_weight.value * traits_score
+        + social.trust_weight.value * trust)
+        / total_weight
+}
+
+#[measured]
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn partnership_system(
+    interactions: Query<&PeopleInteraction>,
+    males: Query<(&Age, &FoodAmount, &Genome), (With<Male>, With<Fertile>, With<Person>)>,
+    females: Query<(&Age, &FoodAmount, &Genome), (With<Female>, With<Fertile>, With<Person>)>,
+    mut partners: Query<&mut Partners>,
+    relationships: Res<Relationships>,
+    config: Res<Config>,
+    time: Res<TotalTicks>,
+    mut stats: ResMut<Statistics>,
+) {
+    for interaction in interactions.iter() {
+        if let (
+            Ok((male_age, male_food, male_genome)),
+            Ok((female_age, female_food, female_genome)),
+        ) = (males.get(interaction.a), females.get(interaction.b))
+        {
+            if let Ok([mut male_partners, mut female_partners]) =
+                partners.get_many_mut([interaction.a, interaction.b])
+            {
+                let max_partners = config.social.max_partners.value as usize;
+                if male_partners.0.len() >= max_partners
+                    || female_partners.0.len() >= max_partners
+                    || male_partners.contains(interaction.b)
+                {
+                    continue;
+                }
+                let choosiness = config.social.choosiness.value;
+                let trust = relationships.trust(interaction.a, interaction.b);
+                let male_choice = attractiveness(
+                    &config,
+                    male_age.0,
+                    female_age.0,
+                    female_food,
+                    female_genome,
+                    trust,
+                );
+                let female_choice = attractiveness(
+                    &config,
+                    female_age.0,
+                    male_age.0,
+                    male_food,
+                    male_genome,
+                    trust,
+                );
+                if male_choice >= choosiness && female_choice >= choosiness {
+                    male_partners.0.push(Partnership {
+                        partner: interaction.b,
+                        since: time.0,
+                    });
+                    female_partners.0.push(Partnership {
+                        partner: interaction.a,
+                        since: time.0,
+                    });
+                    stats.partner_matches.push(PartnerMatch {
+                        male_wealth: (male_food.apples + male_food.oranges) as f32,
+                        female_wealth: (female_food.apples + female_food.oranges) as f32,
+                        male_age: male_age.0 as f32,
+                        female_age: female_age.0 as f32,
+                    });
+                    debug!(
+                        "{} and {} became partners",
+                        interaction.a.index(),
+                        interaction.b.index()
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Partners next to each other even out their food by the configured fraction of the difference
+#[measured]
+pub fn household_sharing_system(
+    interactions: Query<&PeopleInteraction>,
+    partners: Query<&Partners, With<Person>>,
+    mut food: Query<&mut FoodAmount, With<Person>>,
+    config: Res<Config>,
+) {
+    let sharing = config.social.household_sharing.value;
+    if sharing <= 0.0 {
+        return;
+    }
+    for interaction in interactions.iter() {
+        // every pair of neighbours has two interactions, share only once
+        if interaction.a.index() > interaction.b.index() {
+            continue;
+        }
+        if let Ok(a_partners) = partners.get(interaction.a) {
+            if !a_partners.contains(interaction.b) {
+                continue;
+            }
+            if let Ok([mut a_food, mut b_food]) = food.get_many_mut([interaction.a, interaction.b])
+            {
+                let (apples_a, apples_b) = share(a_food.apples, b_food.apples, sharing);
+                let (oranges_a, oranges_b) = share(a_food.oranges, b_food.oranges, sharing);
+                a_food.apples = apples_a;
+                b_food.apples = apples_b;
+                a_food.oranges = oranges_a;
+                b_food.oranges = oranges_b;
+            }
+        }
+    }
+}
+
+pub(crate) fn share(a: u32, b: u32, sharing: f32) -> (u32, u32) {
+    if a > b {
+        let transfer = ((a - b) as f32 / 2.0 * sharing) as u32;
+        (a - transfer, b + transfer)
+    } else {
+        let transfer = ((b - a) as f32 / 2.0 * sharing) as u32;
+        (a + transfer, b - transfer)
+    }
+}
+
+#[measured]
+#[allow(clippy::type_complexity)]
+pub fn end_partnerships_system(
+    died: Query<(Entity, &Partners), (With<Dead>, Added<Dead>)>,
+    mut living: Query<&mut Partners, Without<Dead>>,
+    time: Res<TotalTicks>,
+    mut stats: ResMut<Statistics>,
+) {
+    for (person, partnerships) in died.iter() {
+        for partnership in partnerships.0.iter() {
+            if let Ok(mut partners) = living.get_mut(partnership.partner) {
+                partners.0.retain(|p| p.partner != person);
+                stats.partnership_durations.push(time.0 - partnership.since);
+            } else if died.contains(partnership.partner) && person < partnership.partner {
+                // both partners died at once, the partnership ended only once
+                stats.partnership_durations.push(time.0 - partnership.since);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logic::testing;
+
+    #[quickcheck]
+    fn sharing_should_not_create_or_destroy_food(a: u32, b: u32, sharing: f32) -> bool {
+        if !(0.0..=1.0).contains(&sharing) || a > 1_000_000 || b > 1_000_000 {
+            return true;
+        }
+        let (new_a, new_b) = share(a, b, sharing);
+        new_a + new_b == a + b
+    }
+
+    #[test]
+    fn full_sharing_should_even_out_food() {
+        assert_eq!(share(10, 0, 1.0), (5, 5));
+        assert_eq!(share(0, 10, 1.0), (5, 5));
+        assert_eq!(share(10, 0, 0.0), (10, 0));
+    }
+
+    #[test]
+    fn partnership_should_end_once_when_both_partners_die() {
+        let mut world = testing::world();
+        world.insert_resource(TotalTicks(30));
+        world.insert_resource(Statistics::default());
+        let a = world.spawn(Dead).id();
+        let b = world
+            .spawn((
+                Dead,
+                Partners(vec![Partnership {
+                    partner: a,
+                    since: 10,
+                }]),
+            ))
+            .id();
+        world.entity_mut(a).insert(Partners(vec![Partnership {
+            partner: b,
+            since: 10,
+        }]));
+        let mut schedule = Schedule::new();
+        schedule.add_system(end_partnerships_system);
+        schedule.run(&mut world);
+        assert_eq!(
+            world.resource::<Statistics>().partnership_durations,
+            vec![20]
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::config::Config;
+use crate::logic::measures::{GeometryType, RealCoords};
+
+/// Shape of the map used to find neighbouring tiles and shortest distances
+#[derive(Clone, Copy, Debug)]
+pub struct Grid {
+    pub size_x: u32,
+    pub size_y: u32,
+    pub wrap_x: bool,
+    pub wrap_y: bool,
+}
+
+impl Grid {
+    pub fn new(size_x: u32, size_y: u32, geometry: GeometryType) -> Grid {
+        let (wrap_x, wrap_y) = match geometry {
+            GeometryType::Torus => (true, true),
+            GeometryType::FlatEarth => (false, false),
+            GeometryType::RingVertical => (false, true),
+            GeometryType::RingHorizontal => (true, false),
+        };
+        Grid {
+            size_x,
+            size_y,
+            wrap_x,
+            wrap_y,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Grid {
+        Grid::new(
+            config.map.size_x.value,
+            config.map.size_y.value,
+            config.map.geometry.value,
+        )
+    }
+
+    /// Manhattan distance taking the shorter way around wrapped edges
+    pub fn distance(&self, a: RealCoords, b: RealCoords) -> u32 {
+        axis_distance(a.x, b.x, self.size_x, self.wrap_x)
+            + axis_distance(a.y, b.y, self.size_y, self.wrap_y)
+    }
+
+    /// Tiles reachable in one step without diagonals
+    pub fn neighbours(&self, coords: RealCoords) -> Vec<RealCoords> {
+        [(1, 0), (-1, 0), (0, 1), (0, -1)]
+            .into_iter()
+            .filter_map(|(dx, dy)| self.offset(coords, dx, dy))
+            .collect()
+    }
+
+    /// Tile at given offset, `None` when it is beyond an edge that does not wrap
+    pub fn offset(&self, coords: RealCoords, dx: i32, dy: i32) -> Option<RealCoords> {
+        let x = step(coords.x, dx, self.size_x, self.wrap_x)?;
+        let y = step(coords.y, dy, self.size_y, self.wrap_y)?;
+        Some(RealCoords { x, y })
+    }
+
+    /// Step between neighbouring tiles, a step over a wrapped edge is still a single tile
+    pub fn direction(&self, from: RealCoords, to: RealCoords) -> (i32, i32) {
+        (
+            axis_direction(from.x, to.x, self.size_x, self.wrap_x),
+            axis_direction(from.y, to.y, self.size_y, self.wrap_y),
+        )
+    }
+}
+
+fn axis_distance(a: u32, b: u32, size: u32, wrap: bool) -> u32 {
+    let distance = a.abs_diff(b);
+    if wrap {
+        distance.min(size - distance)
+    } else {
+        distance
+    }
+}
+
+fn axis_direction(from: u32, to: u32, size: u32, wrap: bool) -> i32 {
+    let delta = to as i32 - from as i32;
+    if wrap && delta.unsigned_abs() > size / 2 {
+        -delta.signum()
+    } else {
+        delta.signum()
+    }
+}
+
+fn step(value: u32, delta: i32, size: u32, wrap: bool) -> Option<u32> {
+    let next = value as i32 + delta;
+    if (0..size as i32).contains(&next) {
+        Some(next as u32)
+    } else if wrap && size > 0 {
+        Some(next.rem_euclid(size as i32) as u32)
+    } else {
+        None
+    }
+}
+
+/// A* search of the cheapest path. `cost` gives the cost of entering a tile, `None` for tiles
+/// that can not be entered. Returns the steps after `from` in reverse order, so the next step
+/// can be popped from the end, or `None` when the goal is unreachable within `max_nodes`
+/// expanded tiles.
+pub fn find_path(
+    grid: &Grid,
+    from: RealCoords,
+    to: RealCoords,
+    max_nodes: usize,
+    cost: impl Fn(RealCoords) -> Option<u32>,
+) -> Option<Vec<RealCoords>> {
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<RealCoords, RealCoords> = HashMap::new();
+    let mut best_cost: HashMap<RealCoords, u32> = HashMap::from([(from, 0)]);
+    open.push(Reverse((grid.distance(from, to), 0, (from.x, from.y))));
+    let mut expanded = 0;
+    while let Some(Reverse((_, current_cost, (x, y)))) = open.pop() {
+        let current = RealCoords { x, y };
+        if current == to {
+            let mut path = vec![current];
+            let mut tile = current;
+            while let Some(previous) = came_from.get(&tile) {
+                if *previous == from {
+                    break;
+                }
+                path.push(*previous);
+                tile = *previous;
+            }
+            return if from == to { Some(vec![]) } else { Some(path) };
+        }
+        if current_cost > best_cost.get(&current).copied().unwrap_or(u32::MAX) {
+            continue;
+        }
+        expanded += 1;
+        if expanded > max_nodes {
+            return None;
+        }
+        for next in grid.neighbours(current) {
+            if let Some(step_cost) = cost(next) {
+                let next_cost = current_cost + step_cost;
+                if next_cost < best_cost.get(&next).copied().unwrap_or(u32::MAX) {
+                    best_cost.insert(next, next_cost);
+                    came_from.insert(next, current);
+                    let estimate = next_cost + grid.distance(next, to);
+                    open.push(Reverse((estimate, next_cost, (next.x, next.y))));
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coords(x: u32, y: u32) -> RealCoords {
+        RealCoords { x, y }
+    }
+
+    #[test]
+    fn path_should_go_around_wrapped_edge() {
+        let grid = Grid::new(10, 10, GeometryType::Torus);
+        let path = find_path(&grid, coords(0, 0), coords(8, 0), 100, |_| Some(1)).unwrap();
+        assert_eq!(path, vec![coords(8, 0), coords(9, 0)]);
+        assert_eq!(grid.direction(coords(0, 0), coords(9, 0)), (-1, 0));
+    }
+
+    #[test]
+    fn path_should_not_cross_edge_of_flat_earth() {
+        let grid = Grid::new(10, 10, GeometryType::FlatEarth);
+        let path = find_path(&grid, coords(0, 0), coords(8, 0), 100, |_| Some(1)).unwrap();
+        assert_eq!(path.len(), 8);
+        assert_eq!(grid.distance(coords(0, 0), coords(8, 0)), 8);
+    }
+
+    #[test]
+    fn path_should_avoid_blocked_tiles() {
+        let grid = Grid::new(5, 5, GeometryType::FlatEarth);
+        let wall = |tile: RealCoords| {
+            if tile.x == 1 && tile.y < 4 {
+                None
+            } else {
+                Some(1)
+            }
+        };
+        let path = find_path(&grid, coords(0, 0), coords(2, 0), 100, wall).unwrap();
+        assert_eq!(path.len(), 10);
+        assert!(path.iter().all(|tile| wall(*tile).is_some()));
+        assert_eq!(find_path(&grid, coords(0, 0), coords(2, 0), 5, wall), None);
+    }
+
+    #[test]
+    fn path_to_current_tile_should_be_empty() {
+        let grid = Grid::new(5, 5, GeometryType::Torus);
+        assert_eq!(
+            find_path(&grid, coords(1, 1), coords(1, 1), 10, |_| Some(1)),
+            Some(vec![])
+        );
+    }
+
+    #[quickcheck]
+    fn wrapped_distance_should_not_exceed_flat_distance(a: (u8, u8), b: (u8, u8)) -> bool {
+        let torus = Grid::new(256, 256, GeometryType::Torus);
+        let flat = Grid::new(256, 256, GeometryType::FlatEarth);
+        let (a, b) = (
+            coords(a.0 as u32, a.1 as u32),
+            coords(b.0 as u32, b.1 as u32),
+        );
+        torus.distance(a, b) <= flat.distance(a, b) && torus.distance(a, b) <= 256
+    }
+}
@@ -4,18 +4,34 @@ use big_brain::thinker::ThinkerBuilder;
 use macros::measured;
 use rand::random;
 use rand::seq::IteratorRandom;
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 
 use crate::config::Config;
+use crate::logic::abilities::LifeStage;
+use crate::logic::childhood::Guardian;
 use crate::logic::components::Lookup;
+use crate::logic::genetics::Genome;
+use crate::logic::goap::GoapAgent;
+use crate::logic::health::Health;
+use crate::logic::knowledge::Knowledge;
+use crate::logic::lineage::{FamilyName, Genealogy, Offspring};
 use crate::logic::measures::{RealCoords, VirtualCoords};
+use crate::logic::mortality;
+use crate::logic::names;
+use crate::logic::partnership::Partners;
+use crate::logic::pathfinding::{find_path, Grid};
 use crate::logic::planet::FoodType;
+use crate::logic::tribes::{Territory, TribeMember};
 
 use super::{
     components::{FoodSource, Name, Ttl},
     planet::FoodAmount,
 };
 
+/// Cost of entering a tile when planning paths, every tile costs the same until there is terrain
+const TERRAIN_COST: u32 = 1;
+
 #[derive(Component)]
 pub struct Hunger {
     pub apple: f32,
@@ -46,39 +62,87 @@ pub struct Age(pub u32);
 #[derive(Component)]
 pub struct Dead;
 
+pub const CAUSE_OF_DEATH_COUNT: usize = 5;
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CauseOfDeath {
+    AppleStarvation,
+    OrangeStarvation,
+    OldAge,
+    Disease,
+    Other,
+}
+
+impl CauseOfDeath {
+    pub const ALL: [CauseOfDeath; CAUSE_OF_DEATH_COUNT] = [
+        CauseOfDeath::AppleStarvation,
+        CauseOfDeath::OrangeStarvation,
+        CauseOfDeath::OldAge,
+        CauseOfDeath::Disease,
+        CauseOfDeath::Other,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CauseOfDeath::AppleStarvation => "Apple starvation",
+            CauseOfDeath::OrangeStarvation => "Orange starvation",
+            CauseOfDeath::OldAge => "Old age",
+            CauseOfDeath::Disease => "Disease",
+            CauseOfDeath::Other => "Other",
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Age in ticks at the moment of death
+#[derive(Component)]
+pub struct AgeAtDeath(pub u32);
+
 #[derive(Component)]
 pub struct MoveTo {
     pub dest: VirtualCoords,
+    /// Planned steps with the next one at the end, planned again when empty or blocked
+    pub path: Vec<RealCoords>,
+}
+
+impl MoveTo {
+    pub fn new(dest: VirtualCoords) -> MoveTo {
+        MoveTo { dest, path: vec![] }
+    }
 }
 
 #[derive(Component)]
 pub struct Forage;
 
-pub struct Information {
-    pub entity: Entity,
-    pub coords: VirtualCoords,
-}
-
+/// Marks people who have followed the growing season to another part of the map
 #[derive(Component)]
-pub struct Knowledge {
-    pub infos: Vec<Information>,
-}
+pub struct Migrant;
 
 #[derive(Bundle)]
 pub struct PersonBundle {
     pub name: Name,
+    pub family_name: FamilyName,
+    pub offspring: Offspring,
     pub type_marker: Person,
     pub age: Age,
     pub hunger: Hunger,
     pub food: FoodAmount,
     pub position: VirtualCoords,
     pub knowledge: Knowledge,
+    pub genome: Genome,
+    pub partners: Partners,
+    pub health: Health,
 }
 
 impl Default for PersonBundle {
     fn default() -> Self {
         PersonBundle {
             name: Name(String::from("Test guy")),
+            family_name: FamilyName(String::from("Test")),
+            offspring: Offspring::default(),
             type_marker: Person,
             age: Age(0),
             hunger: Hunger {
@@ -90,7 +154,10 @@ impl Default for PersonBundle {
                 oranges: 3,
             },
             position: VirtualCoords { x: 5, y: 3 },
-            knowledge: Knowledge { infos: Vec::new() },
+            knowledge: Knowledge::default(),
+            genome: Genome::default(),
+            partners: Partners::default(),
+            health: Health::default(),
         }
     }
 }
@@ -108,6 +175,7 @@ impl Plugin for PeoplePlugin {
                 entities: HashMap::new(),
                 default: None,
             })
+            .init_resource::<Genealogy>()
             .add_system(hunger_system)
             .add_system(move_system)
             .add_system(foraging_system)
@@ -137,21 +205,26 @@ pub fn init_people(
     while lookup.entities.len() < people_to_spawn as usize {
         let x = random::<u32>() % config.map.size_x.value;
         let y = random::<u32>() % config.map.size_y.value;
-        if lookup.entities.get(&RealCoords { x, y }).is_none() {
+        if let Entry::Vacant(slot) = lookup.entities.entry(RealCoords { x, y }) {
+            let male = random::<bool>();
+            let family_name = names::family_name();
             let mut person = commands.spawn(PersonBundle {
+                name: Name(names::full_name(&names::given_name(male), &family_name)),
+                family_name: FamilyName(family_name),
                 position: VirtualCoords {
                     x: x as i32,
                     y: y as i32,
                 },
                 age: Age(random::<u32>() % config.game.max_person_age.value),
+                genome: Genome::random(config.game.initial_trait_variation.value),
                 ..default()
             });
-            if random::<u8>() % 2 == 0 {
+            if male {
                 person.insert(Male);
             } else {
                 person.insert(Female);
             }
-            lookup.entities.insert(RealCoords { x, y }, person.id());
+            slot.insert(person.id());
             trace!(
                 "Person spawned at {}, {}. Lookup size: {}",
                 x,
@@ -163,26 +236,30 @@ pub fn init_people(
 }
 
 #[measured]
-fn hunger_system(mut query: Query<(&Person, &mut Hunger), Without<Dead>>, config: Res<Config>) {
-    for (_, mut hunger) in query.iter_mut() {
-        hunger.apple += config.game.hunger_increase.value;
-        hunger.orange += config.game.hunger_increase.value;
+fn hunger_system(
+    mut query: Query<(&Person, &mut Hunger, &Genome), Without<Dead>>,
+    config: Res<Config>,
+) {
+    for (_, mut hunger, genome) in query.iter_mut() {
+        hunger.apple += config.game.hunger_increase.value * genome.metabolism;
+        hunger.orange += config.game.hunger_increase.value * genome.metabolism;
     }
 }
 
 #[measured]
 pub fn aging_system(
     mut commands: Commands,
-    mut query: Query<(Entity, &Person, &mut Age), Without<Dead>>,
+    mut query: Query<(Entity, &Person, &mut Age, &Genome), Without<Dead>>,
     config: Res<Config>,
 ) {
-    for (person, _, mut age) in query.iter_mut() {
+    for (person, _, mut age, genome) in query.iter_mut() {
         age.0 += 1;
-        if age.0 > config.game.max_person_age.value && config.game.max_person_age.value > 0 {
-            mark_entity_as_dead(person, &mut commands, &config);
+        if let Some(cause) = mortality::natural_death(&config, age.0, genome) {
+            mark_entity_as_dead(person, cause, &age, &mut commands, &config);
             debug!(
-                "Person {} died of old age being {} turns old",
+                "Person {} died of {:?} being {} turns old",
                 person.index(),
+                cause,
                 age.0
             );
         }
@@ -240,13 +317,11 @@ fn is_in_male_fertile_age(age: u32, config: &Config) -> bool {
 }
 
 fn is_male_old(age: u32, config: &Config) -> bool {
-    (age as f32)
-        > (config.game.max_fertile_age_male.value * config.game.max_person_age.value as f32)
+    age > config.game.max_fertile_age_male.value
 }
 
 fn is_male_child(age: u32, config: &Config) -> bool {
-    (config.game.min_fertile_age_male.value * config.game.max_person_age.value as f32)
-        > (age as f32)
+    age < config.game.min_fertile_age_male.value
 }
 
 fn is_in_female_fertile_age(age: u32, config: &Config) -> bool {
@@ -254,111 +329,170 @@ fn is_in_female_fertile_age(age: u32, config: &Config) -> bool {
 }
 
 fn is_female_child(age: u32, config: &Config) -> bool {
-    (config.game.min_fertile_age_female.value * config.game.max_person_age.value as f32)
-        > (age as f32)
+    age < config.game.min_fertile_age_female.value
 }
 
 fn is_female_old(age: u32, config: &Config) -> bool {
-    (age as f32)
-        > (config.game.max_fertile_age_female.value * config.game.max_person_age.value as f32)
+    age > config.game.max_fertile_age_female.value
 }
 
-pub fn mark_entity_as_dead(person: Entity, commands: &mut Commands, config: &Res<Config>) {
-    commands
-        .entity(person)
-        .insert(Dead)
-        .insert(Ttl(config.game.person_ttl.value))
-        .remove::<Person>()
-        .remove::<ThinkerBuilder>();
+/// Several systems can kill the same person in one tick, the cause applied first is kept
+pub fn mark_entity_as_dead(
+    person: Entity,
+    cause: CauseOfDeath,
+    age: &Age,
+    commands: &mut Commands,
+    config: &Res<Config>,
+) {
+    let age = AgeAtDeath(age.0);
+    let ttl = Ttl(config.game.person_ttl.value);
+    commands.add(move |world: &mut World| {
+        let Some(mut entity) = world.get_entity_mut(person) else {
+            return;
+        };
+        if entity.contains::<Dead>() {
+            debug!("{} is already dead, not of {:?}", person.index(), cause);
+            return;
+        }
+        entity.insert((Dead, cause, age, ttl));
+        entity.remove::<Person>();
+        entity.remove::<ThinkerBuilder>();
+        entity.remove::<GoapAgent>();
+    });
 }
 
+/// Children with a guardian are led by `follow_guardian_system` instead
 #[measured]
+#[allow(clippy::type_complexity)]
 pub fn move_system(
     mut commands: Commands,
-    mut query: Query<(Entity, &mut MoveTo, &VirtualCoords)>,
+    mut query: Query<
+        (
+            Entity,
+            &mut MoveTo,
+            &VirtualCoords,
+            Option<&Health>,
+            Option<&Child>,
+            Option<&Old>,
+        ),
+        Without<Guardian>,
+    >,
     config: Res<Config>,
     mut person_lookup: ResMut<Lookup<Person>>,
 ) {
-    for (person, move_component, coords) in query.iter_mut() {
-        let move_vector = VirtualCoords {
-            x: move_component.dest.x - coords.to_real(&config).x as i32,
-            y: move_component.dest.y - coords.to_real(&config).y as i32,
-        };
-        let (delta_x, delta_y) =
-            // horizontal move
-            if move_vector.x > 0 {
-                (1, 0)
-            } else if move_vector.x < 0 {
-                (-1, 0)
-            } else if move_vector.y > 0 {
-                (0, 1)
-            } else if move_vector.y < 0 {
-                (0, -1)
-            } else {
-                (0, 0)
-            };
-        if delta_x == 0 && delta_y == 0 {
+    let grid = Grid::from_config(&config);
+    for (person, mut move_component, coords, health, child, old) in query.iter_mut() {
+        // children, the old and weak people do not manage to make a step every frame
+        let strength = health.map(|h| h.strength(&config)).unwrap_or(1.0);
+        if random::<f32>() >= strength * LifeStage::of(child, old).speed(&config) {
+            continue;
+        }
+        let from = coords.to_real(&config);
+        let to = move_component.dest.to_real(&config);
+        if from == to {
             commands.entity(person).remove::<MoveTo>();
+            continue;
+        }
+        let blocked = |tile: &RealCoords| *tile != to && person_lookup.entities.contains_key(tile);
+        if move_component.path.last().is_none_or(&blocked) {
+            let occupied_cost = config.ai.occupied_tile_cost.value;
+            match find_path(
+                &grid,
+                from,
+                to,
+                config.ai.max_path_search.value as usize,
+                |tile| {
+                    if blocked(&tile) {
+                        Some(TERRAIN_COST + occupied_cost)
+                    } else {
+                        Some(TERRAIN_COST)
+                    }
+                },
+            ) {
+                Some(path) => move_component.path = path,
+                None => {
+                    debug!("Person {} can not find a path to {:?}", person.index(), to);
+                    commands.entity(person).remove::<MoveTo>();
+                    continue;
+                }
+            }
         }
+        let next = match move_component.path.last() {
+            Some(next) if !blocked(next) => *next,
+            _ => {
+                debug!(
+                    "Person {} is blocked on the way to {:?} and waits",
+                    person.index(),
+                    to
+                );
+                continue;
+            }
+        };
+        let (delta_x, delta_y) = grid.direction(from, next);
         trace!(
-            "Person {} moved from {:?} by {:?} (move vector {:?})",
+            "Person {} moved from {:?} by {:?} towards {:?}",
             person.index(),
             coords,
             (delta_x, delta_y),
-            move_vector
+            to
         );
         let new_position = VirtualCoords {
             x: coords.x + delta_x,
             y: coords.y + delta_y,
         };
-        if person_lookup
-            .entities
-            .get(&new_position.to_real(&config))
-            .is_none()
-        {
+        if let Entry::Vacant(slot) = person_lookup.entities.entry(next) {
             commands.entity(person).insert(new_position);
-            person_lookup
-                .entities
-                .insert(new_position.to_real(&config), person);
-            person_lookup.entities.remove(&coords.to_real(&config));
-        } else {
-            debug!(
-                "Person {} tried to move to {:?} but there is already someone there",
-                person.index(),
-                new_position
-            );
+            slot.insert(person);
+            person_lookup.entities.remove(&from);
+            move_component.path.pop();
         }
     }
 }
 
 #[measured]
 #[allow(clippy::type_complexity)]
-fn foraging_system(
+pub(crate) fn foraging_system(
     mut commands: Commands,
     mut people: Query<
-        (Entity, &mut FoodAmount, &VirtualCoords),
+        (
+            Entity,
+            &mut FoodAmount,
+            &VirtualCoords,
+            Option<&TribeMember>,
+            Option<&Child>,
+            Option<&Old>,
+        ),
         (Changed<Forage>, With<Person>, With<Forage>),
     >,
     mut food_producers: Query<(&mut FoodAmount, &VirtualCoords, &FoodSource), Without<Person>>,
     food_lookup: Res<Lookup<FoodSource>>,
+    territory: Res<Territory>,
     config: Res<Config>,
 ) {
-    for (person, mut person_food_amount, coords) in people.iter_mut() {
-        if let Some(food) = food_lookup.entities.get(&coords.to_real(&config)) {
+    for (person, mut person_food_amount, coords, member, child, old) in people.iter_mut() {
+        let real_coords = coords.to_real(&config);
+        if territory.excludes(real_coords, member, &config) {
+            debug!(
+                "Person {} can not forage in territory of another tribe",
+                person.index()
+            );
+            commands.entity(person).remove::<Forage>();
+            continue;
+        }
+        if let Some(food) = food_lookup.entities.get(&real_coords) {
             if let Ok((mut food_amount, _, source)) = food_producers.get_mut(*food) {
                 debug!("Found some food!");
+                let harvest = LifeStage::of(child, old).harvest(&config, random());
                 match source.0 {
                     FoodType::Apple => {
-                        if food_amount.apples > 0 {
-                            person_food_amount.apples += 1;
-                            food_amount.apples -= 1;
-                        }
+                        let picked = harvest.min(food_amount.apples);
+                        person_food_amount.apples += picked;
+                        food_amount.apples -= picked;
                     }
                     FoodType::Orange => {
-                        if food_amount.oranges > 0 {
-                            person_food_amount.oranges += 1;
-                            food_amount.oranges -= 1;
-                        }
+                        let picked = harvest.min(food_amount.oranges);
+                        person_food_amount.oranges += picked;
+                        food_amount.oranges -= picked;
                     }
                 }
                 commands.entity(person).remove::<Forage>();
@@ -386,7 +520,7 @@ pub fn free_neighbouring_coords(
             let real_position = new_position.to_real(config);
             let origin_position = coords.to_real(config);
             if real_position != origin_position
-                && lookup.entities.get(&new_position.to_real(config)).is_none()
+                && !lookup.entities.contains_key(&new_position.to_real(config))
             {
                 result.push(new_position);
             }
@@ -414,7 +548,7 @@ pub fn occupied_neighbouring_coords(
             let real_position = new_position.to_real(config);
             let origin_position = coords.to_real(config);
             if real_position != origin_position
-                && lookup.entities.get(&new_position.to_real(config)).is_some()
+                && lookup.entities.contains_key(&new_position.to_real(config))
             {
                 result.push(new_position);
             }
@@ -0,0 +1,181 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+
+use crate::config::Config;
+use crate::logic::thinkers::Thinkers;
+
+pub const PERSONALITIES_PATH: &str = "./data/personalities.json";
+
+/// Strategy of a group of people, replacing the shared values of the AI configuration
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ProfileDefinition {
+    pub description: String,
+    /// Proportion of people getting this profile, relative to the shares of the other profiles
+    pub share: f32,
+    pub thinker: String,
+    pub food_amount_goal: u32,
+    pub food_amount_threshold: f32,
+    pub vision_range: u32,
+}
+
+/// Personality profiles loaded from a file, assigned to new people when enabled in the AI
+/// configuration
+#[derive(Serialize, Deserialize, Debug, Resource)]
+pub struct Personalities {
+    pub profiles: BTreeMap<String, ProfileDefinition>,
+}
+
+impl Personalities {
+    pub fn load(path: &str, thinkers: &Thinkers) -> Result<Personalities, String> {
+        let data =
+            fs::read_to_string(path).map_err(|e| format!("Unable to read {}: {}", path, e))?;
+        Personalities::parse(&data, thinkers)
+            .map_err(|e| format!("Invalid personalities in {}: {}", path, e))
+    }
+
+    pub fn parse(data: &str, thinkers: &Thinkers) -> Result<Personalities, String> {
+        let personalities: Personalities = serde_json::from_str(data).map_err(|e| e.to_string())?;
+        let errors = personalities.validate(thinkers);
+        if errors.is_empty() {
+            Ok(personalities)
+        } else {
+            Err(errors.join(", "))
+        }
+    }
+
+    fn validate(&self, thinkers: &Thinkers) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.profiles.values().all(|profile| profile.share <= 0.0) {
+            errors.push("no profile has a positive share".to_string());
+        }
+        for (name, profile) in self.profiles.iter() {
+            if profile.share < 0.0 {
+                errors.push(format!("profile {} has negative share", name));
+            }
+            if !thinkers.thinkers.contains_key(&profile.thinker) {
+                errors.push(format!(
+                    "profile {} uses unknown thinker {}",
+                    name, profile.thinker
+                ));
+            }
+            if profile.food_amount_goal == 0 {
+                errors.push(format!("profile {} has no food amount goal", name));
+            }
+        }
+        errors
+    }
+
+    /// Profile for a roll from 0.0 to 1.0, each profile is picked with the probability of its
+    /// share
+    pub fn pick(&self, roll: f32) -> (&String, &ProfileDefinition) {
+        let total: f32 = self.profiles.values().map(|p| p.share.max(0.0)).sum();
+        let mut remaining = roll * total;
+        let mut picked = None;
+        for (name, profile) in self.profiles.iter().filter(|(_, p)| p.share > 0.0) {
+            picked = Some((name, profile));
+            if remaining < profile.share {
+                break;
+            }
+            remaining -= profile.share;
+        }
+        picked.expect("validated personalities have a profile with positive share")
+    }
+}
+
+/// Personality profile of a person and the values it gives to their AI
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct Personality {
+    pub profile: String,
+    pub food_amount_goal: u32,
+    pub food_amount_threshold: f32,
+    pub vision_range: u32,
+}
+
+impl Personality {
+    pub fn new(name: &str, profile: &ProfileDefinition) -> Personality {
+        Personality {
+            profile: name.to_string(),
+            food_amount_goal: profile.food_amount_goal,
+            food_amount_threshold: profile.food_amount_threshold,
+            vision_range: profile.vision_range,
+        }
+    }
+}
+
+/// Food amount at which the person does not care about food at all
+pub fn food_amount_goal(personality: Option<&Personality>, config: &Config) -> u32 {
+    personality.map_or(config.ai.food_amount_goal.value, |p| p.food_amount_goal)
+}
+
+pub fn food_amount_threshold(personality: Option<&Personality>, config: &Config) -> f32 {
+    personality.map_or(config.ai.food_amount_threshold.value, |p| {
+        p.food_amount_threshold
+    })
+}
+
+/// Vision range before the genome and the stage of life are applied
+pub fn vision_range(personality: Option<&Personality>, config: &Config) -> u32 {
+    personality.map_or(config.ai.vision_range.value, |p| p.vision_range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THINKERS: &str = r#"{
+        "default": "basic",
+        "thinkers": {
+            "basic": {
+                "picker": { "type": "Highest" },
+                "choices": [{ "scorer": { "type": "Hungry" }, "action": "Eat" }]
+            }
+        }
+    }"#;
+
+    const VALID: &str = r#"{
+        "profiles": {
+            "hoarder": {
+                "description": "Keeps a lot of food",
+                "share": 3.0,
+                "thinker": "basic",
+                "food_amount_goal": 1600,
+                "food_amount_threshold": 1.0,
+                "vision_range": 6
+            },
+            "scout": {
+                "description": "Sees far",
+                "share": 1.0,
+                "thinker": "basic",
+                "food_amount_goal": 800,
+                "food_amount_threshold": 1.0,
+                "vision_range": 9
+            }
+        }
+    }"#;
+
+    #[test]
+    fn profiles_should_be_picked_by_share() {
+        let thinkers = Thinkers::parse(THINKERS).unwrap();
+        let personalities = Personalities::parse(VALID, &thinkers).unwrap();
+        assert_eq!(personalities.pick(0.0).0, "hoarder");
+        assert_eq!(personalities.pick(0.74).0, "hoarder");
+        assert_eq!(personalities.pick(0.76).0, "scout");
+        assert_eq!(personalities.pick(1.0).0, "scout");
+    }
+
+    #[test]
+    fn invalid_profiles_should_be_reported() {
+        let thinkers = Thinkers::parse(THINKERS).unwrap();
+        let error = Personalities::parse(
+            &VALID
+                .replace("\"basic\"", "\"smart\"")
+                .replace("3.0", "-3.0"),
+            &thinkers,
+        )
+        .unwrap_err();
+        assert!(error.contains("smart"), "{}", error);
+        assert!(error.contains("negative"), "{}", error);
+    }
+}
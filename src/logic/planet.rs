@@ -1,10 +1,10 @@
 use crate::debug::components::Performance;
 use bevy::prelude::*;
-use bevy::utils::HashMap;
 use macros::measured;
+use rand::Rng;
 
 use crate::config::Config;
-use crate::logic::measures::{RealCoords, VirtualCoords};
+use crate::logic::measures::VirtualCoords;
 use crate::stats::components::Statistics;
 
 #[derive(Component)]
@@ -22,14 +22,49 @@ pub struct FoodAmount {
     pub oranges: u32,
 }
 
-#[derive(Resource)]
-pub struct FoodLookup {
-    pub food: HashMap<RealCoords, Entity>,
-}
-
 #[derive(Resource)]
 pub struct TotalTicks(pub u32);
 
+/// Tree growing on a tile with up to 3 fruits, if any. Apple trees are dense near the origin of
+/// the map and orange trees near the opposite corner.
+pub fn random_food_source(
+    coords: &VirtualCoords,
+    config: &Config,
+    random: &mut impl Rng,
+) -> Option<(FoodSource, FoodAmount)> {
+    let sparsing_speed = 0.4;
+    let size = config.map.size_x.value as f32;
+    let real = coords.to_real(config);
+    if random.gen_range(0.0..1.0)
+        < (size * config.map.apple_tree_tile_probability.value
+            - ((coords.x + coords.y) as f32) * sparsing_speed)
+            / size
+    {
+        Some((
+            FoodSource(FoodType::Apple),
+            FoodAmount {
+                apples: random.gen_range(0..4),
+                oranges: 0,
+            },
+        ))
+    } else if random.gen_range(0.0..1.0)
+        < (size * config.map.orange_tree_tile_probability.value
+            - (((config.map.size_x.value - real.x) + (config.map.size_y.value - real.y)) as f32)
+                * sparsing_speed)
+            / size
+    {
+        Some((
+            FoodSource(FoodType::Orange),
+            FoodAmount {
+                apples: 0,
+                oranges: random.gen_range(0..4),
+            },
+        ))
+    } else {
+        None
+    }
+}
+
 // This system will increase food amount for all food sources
 #[measured]
 pub fn food_growth(
@@ -93,6 +128,81 @@ fn is_in_growing_season(
     }
 }
 
+/// Growing season of a row of the map as seen at a given moment
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Season {
+    Growing { ticks_left: u32 },
+    Dormant { ticks_until_growing: u32 },
+}
+
+/// Predicts the growing season of a row from the band moving over the map in `food_growth`
+pub fn season_forecast(
+    time: u32,
+    planet_height: u32,
+    row: u32,
+    year_length: u32,
+    growing_season_length: f32,
+) -> Season {
+    let year = year_length as f32;
+    let start = (time % year_length) as f32 / year;
+    // part of the year since the band started growing at the row
+    let offset = (row as f32 / planet_height as f32 - start).rem_euclid(1.0);
+    if is_in_growing_season(
+        &TotalTicks(time),
+        planet_height,
+        row,
+        year_length,
+        growing_season_length,
+    ) {
+        Season::Growing {
+            ticks_left: (offset * year).round() as u32,
+        }
+    } else {
+        Season::Dormant {
+            ticks_until_growing: ((offset - growing_season_length).max(0.0) * year).round() as u32,
+        }
+    }
+}
+
+impl Season {
+    /// Season of the row the coordinates are in
+    pub fn at(coords: &VirtualCoords, time: u32, config: &Config) -> Season {
+        season_forecast(
+            time,
+            config.map.size_y.value,
+            coords.to_real(config).y,
+            config.game.year_length.value,
+            config.game.growing_season_length.value,
+        )
+    }
+
+    /// Whether the growing season ends within the given number of ticks, or has already ended
+    pub fn ending_within(&self, ticks: u32) -> bool {
+        match self {
+            Season::Growing { ticks_left } => *ticks_left < ticks,
+            Season::Dormant { .. } => true,
+        }
+    }
+}
+
+/// Row where the growing season started most recently, so it lasts the longest from now on
+pub fn freshest_growing_row(
+    time: u32,
+    planet_height: u32,
+    year_length: u32,
+    growing_season_length: f32,
+) -> Option<u32> {
+    (0..planet_height)
+        .filter_map(|row| {
+            match season_forecast(time, planet_height, row, year_length, growing_season_length) {
+                Season::Growing { ticks_left } => Some((ticks_left, row)),
+                Season::Dormant { .. } => None,
+            }
+        })
+        .max()
+        .map(|(_, row)| row)
+}
+
 #[cfg(test)]
 extern crate quickcheck;
 
@@ -107,72 +217,102 @@ mod tests {
         let year_length = 100;
         let growing_season_length = 0.10;
         let food_location = 0;
-        assert_eq!(
-            is_in_growing_season(
-                &time,
-                planet_height,
-                food_location,
-                year_length,
-                growing_season_length
-            ),
-            true
-        );
+        assert!(is_in_growing_season(
+            &time,
+            planet_height,
+            food_location,
+            year_length,
+            growing_season_length
+        ));
         let food_location = 4;
-        assert_eq!(
-            is_in_growing_season(
-                &time,
-                planet_height,
-                food_location,
-                year_length,
-                growing_season_length
-            ),
-            true
-        );
+        assert!(is_in_growing_season(
+            &time,
+            planet_height,
+            food_location,
+            year_length,
+            growing_season_length
+        ));
         let food_location = 5;
-        assert_eq!(
-            is_in_growing_season(
-                &time,
-                planet_height,
-                food_location,
-                year_length,
-                growing_season_length
-            ),
-            false
-        );
+        assert!(!is_in_growing_season(
+            &time,
+            planet_height,
+            food_location,
+            year_length,
+            growing_season_length
+        ));
         let time = TotalTicks(95);
         let food_location = 48;
+        assert!(is_in_growing_season(
+            &time,
+            planet_height,
+            food_location,
+            year_length,
+            growing_season_length
+        ));
+        let food_location = 0;
+        assert!(is_in_growing_season(
+            &time,
+            planet_height,
+            food_location,
+            year_length,
+            growing_season_length
+        ));
+        let food_location = 3;
+        assert!(!is_in_growing_season(
+            &time,
+            planet_height,
+            food_location,
+            year_length,
+            growing_season_length
+        ));
+    }
+
+    #[test]
+    fn forecast_should_tell_when_the_season_changes() {
+        // the band covers rows 0 to 9 of 20 at the start of a year of 100 ticks
         assert_eq!(
-            is_in_growing_season(
-                &time,
-                planet_height,
-                food_location,
-                year_length,
-                growing_season_length
-            ),
-            true
+            season_forecast(0, 20, 0, 100, 0.5),
+            Season::Growing { ticks_left: 0 }
         );
-        let food_location = 0;
         assert_eq!(
-            is_in_growing_season(
-                &time,
-                planet_height,
-                food_location,
-                year_length,
-                growing_season_length
-            ),
-            true
+            season_forecast(0, 20, 9, 100, 0.5),
+            Season::Growing { ticks_left: 45 }
         );
-        let food_location = 3;
         assert_eq!(
-            is_in_growing_season(
-                &time,
-                planet_height,
-                food_location,
-                year_length,
-                growing_season_length
-            ),
-            false
+            season_forecast(0, 20, 10, 100, 0.5),
+            Season::Dormant {
+                ticks_until_growing: 0
+            }
+        );
+        assert_eq!(
+            season_forecast(80, 20, 2, 100, 0.5),
+            Season::Growing { ticks_left: 30 }
         );
+        assert_eq!(freshest_growing_row(0, 20, 100, 0.5), Some(9));
+        assert_eq!(freshest_growing_row(80, 20, 100, 0.5), Some(5));
+    }
+
+    #[quickcheck]
+    fn forecast_should_agree_with_growing_season(time: u16, row: u8) -> bool {
+        let (planet_height, year_length, growing_season_length) = (80, 1000, 0.66);
+        let (time, row) = (time as u32, row as u32 % planet_height);
+        let growing = is_in_growing_season(
+            &TotalTicks(time),
+            planet_height,
+            row,
+            year_length,
+            growing_season_length,
+        );
+        let ticks =
+            match season_forecast(time, planet_height, row, year_length, growing_season_length) {
+                Season::Growing { ticks_left } => growing && ticks_left <= 660,
+                Season::Dormant {
+                    ticks_until_growing,
+                } => !growing && ticks_until_growing <= 340,
+            };
+        ticks
+            && freshest_growing_row(time, planet_height, year_length, growing_season_length)
+                .is_some()
     }
 
     #[quickcheck]
@@ -192,12 +332,12 @@ mod tests {
             return true;
         }
         let time = TotalTicks(time);
-        return (0..planet_height)
+        (0..planet_height)
             .filter(|&i| {
                 is_in_growing_season(&time, planet_height, i, year_length, growing_season_length)
             })
             .count()
-            == growing_season_length as usize;
+            == growing_season_length as usize
     }
 
     #[quickcheck]
@@ -216,7 +356,7 @@ mod tests {
         ) {
             return true;
         }
-        return if food_location < planet_height {
+        if food_location < planet_height {
             check_boundary_of_season(
                 planet_height,
                 food_location,
@@ -234,7 +374,7 @@ mod tests {
                 growing_season_length,
                 time,
             )
-        };
+        }
     }
 
     fn check_reasonable_boundaries(
@@ -243,20 +383,13 @@ mod tests {
         year_length: u32,
         growing_season_length: f32,
     ) -> bool {
-        if planet_height < 1
-            || year_length < 1
-            || growing_season_length > 1.0
-            || growing_season_length < 0.0
-        {
-            return true;
-        }
-        if food_location > planet_height || food_location < 0 {
+        if planet_height < 1 || year_length < 1 || !(0.0..=1.0).contains(&growing_season_length) {
             return true;
         }
-        if planet_height > 1000 || year_length > 1000 {
+        if food_location > planet_height {
             return true;
         }
-        return false;
+        planet_height > 1000 || year_length > 1000
     }
 
     fn check_boundary_of_season(
@@ -0,0 +1,124 @@
+use crate::debug::components::Performance;
+use bevy::prelude::*;
+use macros::measured;
+use rand::random;
+
+use crate::config::Config;
+use crate::logic::components::{FoodAmount, Hunger, Lookup, Name, Person};
+use crate::logic::genetics::Genome;
+use crate::logic::lineage::{FamilyName, Parents};
+use crate::logic::measures::VirtualCoords;
+use crate::logic::names;
+use crate::logic::people::{free_neighbouring_coords, Female, Male, PersonBundle};
+use crate::stats::components::Statistics;
+
+/// Mother carrying babies, they are born when `ticks_left` reaches zero and there is free space
+#[derive(Component)]
+pub struct Pregnant {
+    pub father: Entity,
+    pub father_genome: Genome,
+    pub family_name: FamilyName,
+    pub ticks_left: u32,
+    pub babies: u32,
+}
+
+/// Mother can not conceive again until this reaches zero
+#[derive(Component)]
+pub struct BirthCooldown(pub u32);
+
+#[measured]
+#[allow(clippy::type_complexity)]
+pub fn pregnancy_system(
+    mut commands: Commands,
+    mut mothers: Query<
+        (
+            Entity,
+            &mut Pregnant,
+            &mut Hunger,
+            &mut FoodAmount,
+            &VirtualCoords,
+            &Genome,
+        ),
+        (With<Person>, With<Female>),
+    >,
+    config: Res<Config>,
+    mut lookup: ResMut<Lookup<Person>>,
+    mut stats: ResMut<Statistics>,
+) {
+    let mut births = 0;
+    for (mother, mut pregnancy, mut hunger, mut food, coords, genome) in mothers.iter_mut() {
+        hunger.apple += config.game.pregnancy_hunger_increase.value;
+        hunger.orange += config.game.pregnancy_hunger_increase.value;
+        if pregnancy.ticks_left > 0 {
+            pregnancy.ticks_left -= 1;
+            continue;
+        }
+        let apples_per_baby = food.apples / 2 / pregnancy.babies;
+        let oranges_per_baby = food.oranges / 2 / pregnancy.babies;
+        while pregnancy.babies > 0 {
+            let free_space = free_neighbouring_coords(&config, coords, &lookup);
+            if free_space.is_empty() {
+                debug!("{} has no space to give birth, waiting", mother.index());
+                break;
+            }
+            let baby_coords = free_space[random::<usize>() % free_space.len()];
+            food.apples -= apples_per_baby;
+            food.oranges -= oranges_per_baby;
+            let male = random::<bool>();
+            let mut baby = commands.spawn(PersonBundle {
+                name: Name(names::full_name(
+                    &names::given_name(male),
+                    &pregnancy.family_name.0,
+                )),
+                family_name: pregnancy.family_name.clone(),
+                food: FoodAmount {
+                    apples: apples_per_baby,
+                    oranges: oranges_per_baby,
+                },
+                position: baby_coords,
+                genome: Genome::inherit(
+                    &pregnancy.father_genome,
+                    genome,
+                    config.game.mutation_probability.value,
+                    config.game.mutation_strength.value,
+                ),
+                ..Default::default()
+            });
+            baby.insert(Parents {
+                father: pregnancy.father,
+                mother,
+            });
+            if male {
+                baby.insert(Male);
+            } else {
+                baby.insert(Female);
+            }
+            lookup
+                .entities
+                .insert(baby_coords.to_real(&config), baby.id());
+            pregnancy.babies -= 1;
+            births += 1;
+        }
+        if pregnancy.babies == 0 {
+            commands
+                .entity(mother)
+                .remove::<Pregnant>()
+                .insert(BirthCooldown(config.game.birth_cooldown.value));
+        }
+    }
+    stats.births_history.push(births);
+}
+
+#[measured]
+pub fn birth_cooldown_system(
+    mut commands: Commands,
+    mut mothers: Query<(Entity, &mut BirthCooldown)>,
+) {
+    for (mother, mut cooldown) in mothers.iter_mut() {
+        if cooldown.0 > 0 {
+            cooldown.0 -= 1;
+        } else {
+            commands.entity(mother).remove::<BirthCooldown>();
+        }
+    }
+}
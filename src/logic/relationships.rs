@@ -0,0 +1,183 @@
+use crate::debug::components::Performance;
+use bevy::prelude::*;
+use macros::measured;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use crate::config::Config;
+use crate::logic::components::{Dead, Name};
+use crate::logic::interactions::PeopleInteraction;
+use crate::logic::planet::TotalTicks;
+
+/// Relationships with trust below this are forgotten
+const MIN_TRUST: f32 = 0.0001;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Relationship {
+    pub interactions: u32,
+    pub trades: u32,
+    /// From 0.0 for strangers to 1.0 for people that fully trust each other
+    pub trust: f32,
+    pub last_interaction: u32,
+}
+
+/// Symmetric graph of relationships between people, every pair is stored once
+#[derive(Resource, Default)]
+pub struct Relationships {
+    pub pairs: HashMap<(Entity, Entity), Relationship>,
+}
+
+fn key(a: Entity, b: Entity) -> (Entity, Entity) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+impl Relationships {
+    pub fn get(&self, a: Entity, b: Entity) -> Option<&Relationship> {
+        self.pairs.get(&key(a, b))
+    }
+
+    pub fn trust(&self, a: Entity, b: Entity) -> f32 {
+        self.get(a, b).map(|r| r.trust).unwrap_or(0.0)
+    }
+
+    pub fn record_interaction(&mut self, a: Entity, b: Entity, trust_gain: f32, time: u32) {
+        let relationship = self.pairs.entry(key(a, b)).or_default();
+        relationship.interactions += 1;
+        relationship.last_interaction = time;
+        relationship.trust = (relationship.trust + trust_gain).min(1.0);
+    }
+
+    pub fn record_trade(&mut self, a: Entity, b: Entity, trust_gain: f32) {
+        let relationship = self.pairs.entry(key(a, b)).or_default();
+        relationship.trades += 1;
+        relationship.trust = (relationship.trust + trust_gain).min(1.0);
+    }
+
+    /// Trust fades by the given fraction, relationships that faded away are forgotten
+    pub fn decay(&mut self, decay: f32) {
+        self.pairs.retain(|_, relationship| {
+            relationship.trust *= 1.0 - decay;
+            relationship.trust > MIN_TRUST
+        });
+    }
+
+    pub fn forget(&mut self, people: &HashSet<Entity>) {
+        self.pairs
+            .retain(|(a, b), _| !people.contains(a) && !people.contains(b));
+    }
+
+    /// Writes the graph as an edge list that can be imported into network analysis tools
+    pub fn export_csv(&self, path: &str, names: &HashMap<Entity, String>) -> std::io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        writeln!(
+            file,
+            "Source,Target,SourceName,TargetName,Interactions,Trades,Weight"
+        )?;
+        for ((a, b), relationship) in self.pairs.iter() {
+            writeln!(
+                file,
+                "{},{},{},{},{},{},{}",
+                a.index(),
+                b.index(),
+                names.get(a).map(String::as_str).unwrap_or(""),
+                names.get(b).map(String::as_str).unwrap_or(""),
+                relationship.interactions,
+                relationship.trades,
+                relationship.trust
+            )?;
+        }
+        file.flush()
+    }
+}
+
+#[measured]
+pub fn relationship_system(
+    interactions: Query<&PeopleInteraction>,
+    mut relationships: ResMut<Relationships>,
+    config: Res<Config>,
+    time: Res<TotalTicks>,
+) {
+    for interaction in interactions.iter() {
+        // every pair of neighbours has two interactions, count only one
+        if interaction.a < interaction.b {
+            relationships.record_interaction(
+                interaction.a,
+                interaction.b,
+                config.social.trust_per_interaction.value,
+                time.0,
+            );
+        }
+    }
+}
+
+#[measured]
+pub fn relationship_decay_system(
+    died: Query<Entity, Added<Dead>>,
+    mut relationships: ResMut<Relationships>,
+    config: Res<Config>,
+) {
+    relationships.decay(config.social.trust_decay.value);
+    let dead = died.iter().collect::<HashSet<_>>();
+    if !dead.is_empty() {
+        relationships.forget(&dead);
+    }
+}
+
+pub fn export_relationships(
+    relationships: &Relationships,
+    people: &Query<(Entity, &Name)>,
+    path: &str,
+) {
+    let names = people
+        .iter()
+        .map(|(entity, name)| (entity, name.0.clone()))
+        .collect::<HashMap<_, _>>();
+    match relationships.export_csv(path, &names) {
+        Ok(()) => info!(
+            "Exported {} relationships to {}",
+            relationships.pairs.len(),
+            path
+        ),
+        Err(error) => error!("Could not export relationships to {}: {}", path, error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relationship_should_not_depend_on_order() {
+        let a = Entity::from_raw(1);
+        let b = Entity::from_raw(2);
+        let mut relationships = Relationships::default();
+        relationships.record_interaction(a, b, 0.1, 0);
+        relationships.record_trade(b, a, 0.2);
+        assert_eq!(relationships.pairs.len(), 1);
+        let relationship = relationships.get(b, a).unwrap();
+        assert_eq!(relationship.interactions, 1);
+        assert_eq!(relationship.trades, 1);
+        assert!((relationships.trust(a, b) - 0.3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn trust_should_fade_and_be_forgotten() {
+        let a = Entity::from_raw(1);
+        let b = Entity::from_raw(2);
+        let c = Entity::from_raw(3);
+        let mut relationships = Relationships::default();
+        relationships.record_trade(a, b, 2.0);
+        relationships.record_trade(a, c, MIN_TRUST * 1.5);
+        assert_eq!(relationships.trust(a, b), 1.0);
+        relationships.decay(0.5);
+        assert_eq!(relationships.trust(a, b), 0.5);
+        assert!(relationships.get(a, c).is_none());
+        relationships.forget(&HashSet::from([b]));
+        assert!(relationships.pairs.is_empty());
+    }
+}
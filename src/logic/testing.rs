@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::config::{Config, CONFIG_PATH};
+use crate::debug::components::Performance;
+use crate::logic::components::{FoodAmount, FoodSource, FoodType, Lookup, Person};
+use crate::logic::decisions::Decisions;
+use crate::logic::genetics::Genome;
+use crate::logic::knowledge::{Information, Knowledge};
+use crate::logic::measures::VirtualCoords;
+use crate::logic::planet::TotalTicks;
+use crate::logic::tribes::Territory;
+use crate::logic::vision::VisionCache;
+
+/// Where the apple tree of `world_with_known_tree` grows
+pub const TREE: VirtualCoords = VirtualCoords { x: 3, y: 0 };
+
+/// An empty world with the configuration from the config file and the resources every measured
+/// system needs
+pub fn world() -> World {
+    let data = std::fs::read_to_string(CONFIG_PATH).unwrap();
+    let config: Config = serde_json::from_str(&data).unwrap();
+    let mut world = World::new();
+    world.insert_resource(config);
+    world.insert_resource(Performance::new(10));
+    world.insert_resource(TotalTicks(0));
+    world
+}
+
+/// A world with one apple tree known to one person standing a few tiles away from it at the origin
+pub fn world_with_known_tree() -> (World, Entity) {
+    let mut world = world();
+    let tree = world
+        .spawn((
+            FoodSource(FoodType::Apple),
+            FoodAmount {
+                apples: 3,
+                oranges: 0,
+            },
+            TREE,
+        ))
+        .id();
+    let mut knowledge = Knowledge::default();
+    knowledge.learn(
+        Information {
+            entity: tree,
+            coords: TREE,
+            observed: 0,
+            reliability: 1.0,
+            apples: 3,
+            oranges: 0,
+        },
+        10,
+    );
+    let origin = VirtualCoords { x: 0, y: 0 };
+    let person = world
+        .spawn((
+            Person,
+            origin,
+            FoodAmount {
+                apples: 0,
+                oranges: 0,
+            },
+            Genome::default(),
+            knowledge,
+            Decisions::default(),
+        ))
+        .id();
+    let config = world.resource::<Config>();
+    let (tree_coords, origin) = (TREE.to_real(config), origin.to_real(config));
+    world.insert_resource(Lookup::<FoodSource> {
+        entities: HashMap::from_iter([(tree_coords, tree)]),
+        default: None,
+    });
+    world.insert_resource(Lookup::<Person> {
+        entities: HashMap::from_iter([(origin, person)]),
+        default: None,
+    });
+    world.init_resource::<VisionCache>();
+    world.init_resource::<Territory>();
+    (world, person)
+}
@@ -0,0 +1,301 @@
+use bevy::prelude::*;
+use big_brain::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fs;
+
+use crate::logic::ai::{
+    Eat, Hungry, LookAround, MatingDesire, Migrate, MissingInfo, MoveAction, MoveNeed,
+    SeasonEnding, SeekMate, SeekTrade, TradeNeed,
+};
+
+pub const THINKERS_PATH: &str = "./data/thinkers.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum PickerDefinition {
+    FirstToScore { threshold: f32 },
+    Highest,
+}
+
+/// Scorers of people and the composite scorers of big_brain that combine them
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum ScorerDefinition {
+    Hungry,
+    MoveNeed {
+        /// Overrides the food amount threshold of the AI configuration
+        #[serde(default)]
+        food_threshold: Option<f32>,
+    },
+    MissingInfo,
+    TradeNeed,
+    MatingDesire,
+    SeasonEnding,
+    Fixed {
+        score: f32,
+    },
+    AllOrNothing {
+        threshold: f32,
+        scorers: Vec<ScorerDefinition>,
+    },
+    SumOfScorers {
+        threshold: f32,
+        scorers: Vec<ScorerDefinition>,
+    },
+    ProductOfScorers {
+        threshold: f32,
+        scorers: Vec<ScorerDefinition>,
+    },
+    WinningScorer {
+        threshold: f32,
+        scorers: Vec<ScorerDefinition>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Display, Clone, Copy, PartialEq, Eq)]
+pub enum ActionDefinition {
+    Eat,
+    Move,
+    LookAround,
+    SeekTrade,
+    SeekMate,
+    Migrate,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChoiceDefinition {
+    pub scorer: ScorerDefinition,
+    pub action: ActionDefinition,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ThinkerDefinition {
+    pub picker: PickerDefinition,
+    pub choices: Vec<ChoiceDefinition>,
+    #[serde(default)]
+    pub otherwise: Option<ActionDefinition>,
+}
+
+/// Thinker variants loaded from a file, people get the one selected in the AI configuration
+#[derive(Serialize, Deserialize, Debug, Resource)]
+pub struct Thinkers {
+    pub default: String,
+    pub thinkers: BTreeMap<String, ThinkerDefinition>,
+}
+
+impl Thinkers {
+    pub fn load(path: &str) -> Result<Thinkers, String> {
+        let data =
+            fs::read_to_string(path).map_err(|e| format!("Unable to read {}: {}", path, e))?;
+        Thinkers::parse(&data).map_err(|e| format!("Invalid thinkers in {}: {}", path, e))
+    }
+
+    pub fn parse(data: &str) -> Result<Thinkers, String> {
+        let thinkers: Thinkers = serde_json::from_str(data).map_err(|e| e.to_string())?;
+        let errors = thinkers.validate();
+        if errors.is_empty() {
+            Ok(thinkers)
+        } else {
+            Err(errors.join(", "))
+        }
+    }
+
+    fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if !self.thinkers.contains_key(&self.default) {
+            errors.push(format!("default thinker {} is not defined", self.default));
+        }
+        for (name, thinker) in self.thinkers.iter() {
+            if thinker.choices.is_empty() && thinker.otherwise.is_none() {
+                errors.push(format!("thinker {} has no choices", name));
+            }
+            if let PickerDefinition::FirstToScore { threshold } = thinker.picker {
+                check_threshold(&mut errors, name, threshold);
+            }
+            for choice in thinker.choices.iter() {
+                check_scorer(&mut errors, name, &choice.scorer);
+            }
+        }
+        errors
+    }
+
+    /// Falls back to the default thinker when the name is not known
+    pub fn get(&self, name: &str) -> &ThinkerDefinition {
+        self.thinkers
+            .get(name)
+            .unwrap_or_else(|| &self.thinkers[&self.default])
+    }
+}
+
+fn check_threshold(errors: &mut Vec<String>, thinker: &str, threshold: f32) {
+    if !(0.0..=1.0).contains(&threshold) {
+        errors.push(format!(
+            "thinker {} has threshold {} outside of 0.0 to 1.0",
+            thinker, threshold
+        ));
+    }
+}
+
+fn check_scorer(errors: &mut Vec<String>, thinker: &str, scorer: &ScorerDefinition) {
+    match scorer {
+        ScorerDefinition::Fixed { score } => check_threshold(errors, thinker, *score),
+        ScorerDefinition::AllOrNothing { threshold, scorers }
+        | ScorerDefinition::SumOfScorers { threshold, scorers }
+        | ScorerDefinition::ProductOfScorers { threshold, scorers }
+        | ScorerDefinition::WinningScorer { threshold, scorers } => {
+            check_threshold(errors, thinker, *threshold);
+            if scorers.is_empty() {
+                errors.push(format!(
+                    "thinker {} has a composite scorer without scorers",
+                    thinker
+                ));
+            }
+            for scorer in scorers {
+                check_scorer(errors, thinker, scorer);
+            }
+        }
+        _ => {}
+    }
+}
+
+impl ThinkerDefinition {
+    pub fn to_builder(&self) -> ThinkerBuilder {
+        let mut builder = match self.picker {
+            PickerDefinition::FirstToScore { threshold } => {
+                Thinker::build().picker(FirstToScore { threshold })
+            }
+            PickerDefinition::Highest => Thinker::build().picker(Highest),
+        };
+        for choice in self.choices.iter() {
+            builder = builder.when(choice.scorer.clone(), choice.action);
+        }
+        if let Some(otherwise) = self.otherwise {
+            builder = builder.otherwise(otherwise);
+        }
+        builder
+    }
+}
+
+impl ScorerBuilder for ScorerDefinition {
+    fn build(&self, cmd: &mut Commands, scorer: Entity, actor: Entity) {
+        match self {
+            ScorerDefinition::Hungry => {
+                cmd.entity(scorer).insert(Hungry);
+            }
+            ScorerDefinition::MoveNeed { food_threshold } => {
+                cmd.entity(scorer).insert(MoveNeed {
+                    food_threshold: *food_threshold,
+                });
+            }
+            ScorerDefinition::MissingInfo => {
+                cmd.entity(scorer).insert(MissingInfo);
+            }
+            ScorerDefinition::TradeNeed => {
+                cmd.entity(scorer).insert(TradeNeed);
+            }
+            ScorerDefinition::MatingDesire => {
+                cmd.entity(scorer).insert(MatingDesire);
+            }
+            ScorerDefinition::SeasonEnding => {
+                cmd.entity(scorer).insert(SeasonEnding);
+            }
+            ScorerDefinition::Fixed { score } => {
+                FixedScore::build(*score).build(cmd, scorer, actor);
+            }
+            ScorerDefinition::AllOrNothing { threshold, scorers } => {
+                let mut builder = AllOrNothing::build(*threshold);
+                for child in scorers {
+                    builder = builder.push(child.clone());
+                }
+                builder.build(cmd, scorer, actor);
+            }
+            ScorerDefinition::SumOfScorers { threshold, scorers } => {
+                let mut builder = SumOfScorers::build(*threshold);
+                for child in scorers {
+                    builder = builder.push(child.clone());
+                }
+                builder.build(cmd, scorer, actor);
+            }
+            ScorerDefinition::ProductOfScorers { threshold, scorers } => {
+                let mut builder = ProductOfScorers::build(*threshold);
+                for child in scorers {
+                    builder = builder.push(child.clone());
+                }
+                builder.build(cmd, scorer, actor);
+            }
+            ScorerDefinition::WinningScorer { threshold, scorers } => {
+                let mut builder = WinningScorer::build(*threshold);
+                for child in scorers {
+                    builder = builder.push(child.clone());
+                }
+                builder.build(cmd, scorer, actor);
+            }
+        }
+    }
+}
+
+impl ActionBuilder for ActionDefinition {
+    fn build(&self, cmd: &mut Commands, action: Entity, _actor: Entity) {
+        match self {
+            ActionDefinition::Eat => cmd.entity(action).insert(Eat),
+            ActionDefinition::Move => cmd.entity(action).insert(MoveAction::default()),
+            ActionDefinition::LookAround => cmd.entity(action).insert(LookAround),
+            ActionDefinition::SeekTrade => cmd.entity(action).insert(SeekTrade::default()),
+            ActionDefinition::SeekMate => cmd.entity(action).insert(SeekMate::default()),
+            ActionDefinition::Migrate => cmd.entity(action).insert(Migrate::default()),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALID: &str = r#"{
+        "default": "basic",
+        "thinkers": {
+            "basic": {
+                "picker": { "type": "FirstToScore", "threshold": 0.8 },
+                "choices": [
+                    { "scorer": { "type": "Hungry" }, "action": "Eat" },
+                    { "scorer": { "type": "MoveNeed", "food_threshold": 0.2 }, "action": "Move" }
+                ]
+            }
+        }
+    }"#;
+
+    #[test]
+    fn valid_thinkers_should_be_parsed() {
+        let thinkers = Thinkers::parse(VALID).unwrap();
+        let basic = thinkers.get("unknown");
+        assert_eq!(basic.choices.len(), 2);
+        assert_eq!(
+            basic.choices[1].scorer,
+            ScorerDefinition::MoveNeed {
+                food_threshold: Some(0.2)
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_scorers_and_actions_should_be_rejected() {
+        let error = Thinkers::parse(&VALID.replace("\"Hungry\"", "\"Thirsty\"")).unwrap_err();
+        assert!(error.contains("Thirsty"), "{}", error);
+        let error = Thinkers::parse(&VALID.replace("\"Eat\"", "\"Drink\"")).unwrap_err();
+        assert!(error.contains("Drink"), "{}", error);
+    }
+
+    #[test]
+    fn invalid_definitions_should_be_reported() {
+        let error = Thinkers::parse(
+            &VALID
+                .replace("\"default\": \"basic\"", "\"default\": \"smart\"")
+                .replace("0.8", "1.8"),
+        )
+        .unwrap_err();
+        assert!(error.contains("smart"), "{}", error);
+        assert!(error.contains("1.8"), "{}", error);
+    }
+}
@@ -0,0 +1,340 @@
+use crate::debug::components::Performance;
+use bevy::prelude::*;
+use macros::measured;
+use std::collections::HashMap;
+
+use crate::config::Config;
+use crate::logic::components::{FoodAmount, Person};
+use crate::logic::interactions::PeopleInteraction;
+use crate::logic::lineage::{FamilyName, Parents};
+use crate::logic::measures::{RealCoords, VirtualCoords};
+use crate::logic::partnership::share;
+use crate::logic::people::Child;
+use crate::logic::planet::TotalTicks;
+use crate::logic::relationships::Relationships;
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TribeMember(pub u32);
+
+pub struct Tribe {
+    pub name: String,
+    pub members: u32,
+    pub founded: u32,
+}
+
+#[derive(Resource, Default)]
+pub struct Tribes {
+    pub tribes: HashMap<u32, Tribe>,
+    next_id: u32,
+}
+
+impl Tribes {
+    fn found(&mut self, name: String, time: u32) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.tribes.insert(
+            id,
+            Tribe {
+                name,
+                members: 0,
+                founded: time,
+            },
+        );
+        id
+    }
+}
+
+pub struct Claim {
+    pub tribe: u32,
+    /// From 0.0 to 1.0, tiles with no strength left are free
+    pub strength: f32,
+}
+
+/// Tiles claimed by tribes, they are claimed by members standing on them and fade when abandoned
+#[derive(Resource, Default)]
+pub struct Territory {
+    pub claims: HashMap<RealCoords, Claim>,
+}
+
+impl Territory {
+    /// Strangers can not forage in a tile held firmly enough by another tribe
+    pub fn excludes(
+        &self,
+        coords: RealCoords,
+        member: Option<&TribeMember>,
+        config: &Config,
+    ) -> bool {
+        match self.claims.get(&coords) {
+            Some(claim) => {
+                claim.strength >= config.tribes.exclusion_threshold.value
+                    && member.map(|m| m.0) != Some(claim.tribe)
+            }
+            None => false,
+        }
+    }
+
+    pub fn areas(&self) -> HashMap<u32, u32> {
+        let mut areas = HashMap::new();
+        for claim in self.claims.values() {
+            *areas.entry(claim.tribe).or_default() += 1;
+        }
+        areas
+    }
+
+    /// Returns true when the tile got a new owner
+    fn claim(&mut self, coords: RealCoords, tribe: u32, gain: f32) -> bool {
+        let mut claimed = false;
+        let claim = self.claims.entry(coords).or_insert_with(|| {
+            claimed = true;
+            Claim {
+                tribe,
+                strength: 0.0,
+            }
+        });
+        if claim.tribe == tribe {
+            claim.strength = (claim.strength + gain).min(1.0);
+        } else {
+            claim.strength -= gain;
+            if claim.strength <= 0.0 {
+                claim.tribe = tribe;
+                claim.strength = -claim.strength;
+                claimed = true;
+            }
+        }
+        claimed
+    }
+
+    /// Returns true when some tiles were abandoned
+    fn decay(&mut self, decay: f32) -> bool {
+        let claimed = self.claims.len();
+        self.claims.retain(|_, claim| {
+            claim.strength -= decay;
+            claim.strength > 0.0
+        });
+        self.claims.len() != claimed
+    }
+}
+
+/// Parents, children and siblings are kin
+fn kin(a: Entity, a_parents: Option<&Parents>, b: Entity, b_parents: Option<&Parents>) -> bool {
+    let parent_of = |parents: Option<&Parents>, parent: Entity| {
+        parents.is_some_and(|p| p.father == parent || p.mother == parent)
+    };
+    let siblings = match (a_parents, b_parents) {
+        (Some(a), Some(b)) => a.father == b.father || a.mother == b.mother,
+        _ => false,
+    };
+    parent_of(a_parents, b) || parent_of(b_parents, a) || siblings
+}
+
+/// Adults who trust each other enough join the tribe of the other one or found a new tribe, kin
+/// need less trust than strangers
+#[measured]
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn tribe_formation_system(
+    mut commands: Commands,
+    interactions: Query<&PeopleInteraction>,
+    adults: Query<
+        (Option<&TribeMember>, &FamilyName, Option<&Parents>),
+        (With<Person>, Without<Child>),
+    >,
+    relationships: Res<Relationships>,
+    mut tribes: ResMut<Tribes>,
+    time: Res<TotalTicks>,
+    config: Res<Config>,
+) {
+    // commands are applied later, so decisions of this frame have to be remembered
+    let mut joined: HashMap<Entity, u32> = HashMap::new();
+    for interaction in interactions.iter() {
+        if interaction.a > interaction.b {
+            continue;
+        }
+        if let (Ok((a_member, a_family, a_parents)), Ok((b_member, _, b_parents))) =
+            (adults.get(interaction.a), adults.get(interaction.b))
+        {
+            let kinship = if kin(interaction.a, a_parents, interaction.b, b_parents) {
+                config.tribes.kinship_trust.value
+            } else {
+                0.0
+            };
+            if relationships.trust(interaction.a, interaction.b) + kinship
+                < config.tribes.join_trust.value
+            {
+                continue;
+            }
+            let a_tribe = a_member
+                .map(|m| m.0)
+                .or_else(|| joined.get(&interaction.a).copied());
+            let b_tribe = b_member
+                .map(|m| m.0)
+                .or_else(|| joined.get(&interaction.b).copied());
+            let (tribe, newcomers) = match (a_tribe, b_tribe) {
+                (Some(tribe), None) => (tribe, vec![interaction.b]),
+                (None, Some(tribe)) => (tribe, vec![interaction.a]),
+                (None, None) => {
+                    let tribe = tribes.found(format!("{} tribe", a_family.0), time.0);
+                    info!("{} was founded", tribes.tribes[&tribe].name);
+                    (tribe, vec![interaction.a, interaction.b])
+                }
+                _ => continue,
+            };
+            for newcomer in newcomers {
+                debug!("{} joined tribe {}", newcomer.index(), tribe);
+                commands.entity(newcomer).insert(TribeMember(tribe));
+                joined.insert(newcomer, tribe);
+            }
+        }
+    }
+}
+
+/// Children are born into the tribe of their mother
+#[measured]
+pub fn tribe_birth_system(
+    mut commands: Commands,
+    born: Query<(Entity, &Parents), Added<Person>>,
+    members: Query<&TribeMember>,
+) {
+    for (child, parents) in born.iter() {
+        if let Ok(tribe) = members.get(parents.mother) {
+            commands.entity(child).insert(*tribe);
+        }
+    }
+}
+
+/// Counts members and dissolves tribes that have less than two living members
+#[measured]
+pub fn tribe_membership_system(
+    mut commands: Commands,
+    members: Query<(Entity, &TribeMember), With<Person>>,
+    mut tribes: ResMut<Tribes>,
+    mut territory: ResMut<Territory>,
+    time: Res<TotalTicks>,
+) {
+    for tribe in tribes.tribes.values_mut() {
+        tribe.members = 0;
+    }
+    for (_, member) in members.iter() {
+        if let Some(tribe) = tribes.tribes.get_mut(&member.0) {
+            tribe.members += 1;
+        }
+    }
+    let dissolved = tribes
+        .tribes
+        .iter()
+        // founders become members only when commands of this tick are applied
+        .filter(|(_, tribe)| tribe.members < 2 && tribe.founded < time.0)
+        .map(|(id, _)| *id)
+        .collect::<Vec<_>>();
+    for id in dissolved {
+        if let Some(tribe) = tribes.tribes.remove(&id) {
+            info!("{} was dissolved", tribe.name);
+        }
+        for (person, member) in members.iter() {
+            if member.0 == id {
+                commands.entity(person).remove::<TribeMember>();
+            }
+        }
+        territory.claims.retain(|_, claim| claim.tribe != id);
+    }
+}
+
+#[measured]
+pub fn territory_system(
+    members: Query<(&TribeMember, &VirtualCoords), With<Person>>,
+    mut territory: ResMut<Territory>,
+    config: Res<Config>,
+) {
+    // strength changes every tick, the territory is marked as changed only when tiles change
+    // owners so the overlay is not repainted needlessly
+    let claims = territory.bypass_change_detection();
+    let mut changed = claims.decay(config.tribes.territory_decay.value);
+    for (member, coords) in members.iter() {
+        changed |= claims.claim(
+            coords.to_real(&config),
+            member.0,
+            config.tribes.territory_claim.value,
+        );
+    }
+    if changed {
+        territory.set_changed();
+    }
+}
+
+/// Members of the same tribe standing next to each other share some of their food
+#[measured]
+pub fn tribe_sharing_system(
+    interactions: Query<&PeopleInteraction>,
+    members: Query<&TribeMember, With<Person>>,
+    mut food: Query<&mut FoodAmount, With<Person>>,
+    config: Res<Config>,
+) {
+    let sharing = config.tribes.sharing.value;
+    if sharing <= 0.0 {
+        return;
+    }
+    for interaction in interactions.iter() {
+        if interaction.a > interaction.b {
+            continue;
+        }
+        if let (Ok(a_tribe), Ok(b_tribe)) = (members.get(interaction.a), members.get(interaction.b))
+        {
+            if a_tribe != b_tribe {
+                continue;
+            }
+            if let Ok([mut a_food, mut b_food]) = food.get_many_mut([interaction.a, interaction.b])
+            {
+                let (apples_a, apples_b) = share(a_food.apples, b_food.apples, sharing);
+                let (oranges_a, oranges_b) = share(a_food.oranges, b_food.oranges, sharing);
+                a_food.apples = apples_a;
+                b_food.apples = apples_b;
+                a_food.oranges = oranges_a;
+                b_food.oranges = oranges_b;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn claims_should_be_taken_over_by_stronger_tribe() {
+        let coords = RealCoords { x: 1, y: 1 };
+        let mut territory = Territory::default();
+        territory.claim(coords, 1, 0.3);
+        territory.claim(coords, 2, 0.5);
+        let claim = &territory.claims[&coords];
+        assert_eq!(claim.tribe, 2);
+        assert!((claim.strength - 0.2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn parents_children_and_siblings_should_be_kin() {
+        let [father, mother, child, sibling, half_sibling, stranger] =
+            [1, 2, 3, 4, 5, 6].map(Entity::from_raw);
+        let parents = Parents { father, mother };
+        let half = Parents {
+            father: stranger,
+            mother,
+        };
+        assert!(kin(child, Some(&parents), mother, None));
+        assert!(kin(father, None, child, Some(&parents)));
+        assert!(kin(child, Some(&parents), sibling, Some(&parents)));
+        assert!(kin(child, Some(&parents), half_sibling, Some(&half)));
+        assert!(!kin(father, None, mother, None));
+        assert!(!kin(child, Some(&parents), stranger, None));
+    }
+
+    #[test]
+    fn abandoned_claims_should_fade() {
+        let coords = RealCoords { x: 1, y: 1 };
+        let mut territory = Territory::default();
+        assert!(territory.claim(coords, 1, 0.3));
+        assert!(!territory.claim(coords, 1, 0.1));
+        assert!(!territory.decay(0.25));
+        assert_eq!(territory.areas().get(&1), Some(&1));
+        assert!(territory.decay(0.25));
+        assert!(territory.areas().is_empty());
+    }
+}
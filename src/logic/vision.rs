@@ -0,0 +1,231 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::logic::components::{FoodSource, Lookup};
+use crate::logic::measures::{GeometryType, RealCoords};
+use crate::logic::pathfinding::Grid;
+
+#[derive(Copy, Clone, Serialize, Deserialize, Debug, Display, PartialEq, Eq, Hash)]
+pub enum VisionShape {
+    /// Only along the row and the column of the person
+    Cross,
+    /// Diamond of tiles reachable within the range of steps
+    Manhattan,
+    /// Square around the person
+    Chebyshev,
+    /// Circle around the person
+    Euclidean,
+}
+
+impl VisionShape {
+    pub fn contains(&self, dx: i32, dy: i32, range: u32) -> bool {
+        let (x, y) = (dx.unsigned_abs(), dy.unsigned_abs());
+        match self {
+            VisionShape::Cross => (x == 0 || y == 0) && x.max(y) <= range,
+            VisionShape::Manhattan => x + y <= range,
+            VisionShape::Chebyshev => x.max(y) <= range,
+            VisionShape::Euclidean => x * x + y * y <= range * range,
+        }
+    }
+
+    /// Offsets of all tiles within the range, nearest first
+    pub fn offsets(&self, range: u32) -> Vec<(i32, i32)> {
+        let r = range as i32;
+        let mut offsets = (-r..=r)
+            .flat_map(|dx| (-r..=r).map(move |dy| (dx, dy)))
+            .filter(|(dx, dy)| self.contains(*dx, *dy, range))
+            .collect::<Vec<_>>();
+        offsets.sort_by_key(|(dx, dy)| dx * dx + dy * dy);
+        offsets
+    }
+}
+
+/// Tiles crossed by a straight line from the origin to given offset, without both ends
+pub fn line_of_sight(dx: i32, dy: i32) -> Vec<(i32, i32)> {
+    // Bresenham's line algorithm
+    let (step_x, step_y) = (dx.signum(), dy.signum());
+    let (width, height) = (dx.abs(), -dy.abs());
+    let mut error = width + height;
+    let (mut x, mut y) = (0, 0);
+    let mut tiles = Vec::new();
+    loop {
+        let double = 2 * error;
+        if double >= height {
+            error += height;
+            x += step_x;
+        }
+        if double <= width {
+            error += width;
+            y += step_y;
+        }
+        if (x, y) == (dx, dy) {
+            return tiles;
+        }
+        tiles.push((x, y));
+    }
+}
+
+/// Whether nothing stands between the origin and the tile at given offset
+pub fn in_sight(
+    grid: &Grid,
+    food_lookup: &Lookup<FoodSource>,
+    origin: RealCoords,
+    dx: i32,
+    dy: i32,
+) -> bool {
+    // trees stand in the way of whatever is behind them
+    !line_of_sight(dx, dy).into_iter().any(|(x, y)| {
+        grid.offset(origin, x, y)
+            .is_some_and(|tile| food_lookup.entities.contains_key(&tile))
+    })
+}
+
+/// Food source seen at an offset from the person
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sighting {
+    pub entity: Entity,
+    pub dx: i32,
+    pub dy: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct VisionSettings {
+    shape: VisionShape,
+    line_of_sight: bool,
+    size_x: u32,
+    size_y: u32,
+    geometry: GeometryType,
+}
+
+impl VisionSettings {
+    fn from_config(config: &Config) -> VisionSettings {
+        VisionSettings {
+            shape: config.ai.vision_shape.value,
+            line_of_sight: config.ai.line_of_sight.value,
+            size_x: config.map.size_x.value,
+            size_y: config.map.size_y.value,
+            geometry: config.map.geometry.value,
+        }
+    }
+}
+
+/// Food sources never move, so what can be seen from a tile only changes with the configuration.
+/// Sightings are remembered for each tile and vision range and the whole cache is dropped when
+/// the vision or map settings change.
+#[derive(Resource, Default)]
+pub struct VisionCache {
+    settings: Option<VisionSettings>,
+    offsets: HashMap<u32, Arc<Vec<(i32, i32)>>>,
+    sightings: HashMap<(RealCoords, u32), Arc<Vec<Sighting>>>,
+}
+
+impl VisionCache {
+    fn refresh(&mut self, config: &Config) -> VisionSettings {
+        let settings = VisionSettings::from_config(config);
+        if self.settings != Some(settings) {
+            self.settings = Some(settings);
+            self.offsets.clear();
+            self.sightings.clear();
+        }
+        settings
+    }
+
+    /// Offsets of the tiles within the vision range in the configured shape, nearest first
+    pub fn offsets(&mut self, config: &Config, range: u32) -> Arc<Vec<(i32, i32)>> {
+        let settings = self.refresh(config);
+        self.offsets
+            .entry(range)
+            .or_insert_with(|| Arc::new(settings.shape.offsets(range)))
+            .clone()
+    }
+
+    /// Food sources visible from a tile, nearest first
+    pub fn food_sources(
+        &mut self,
+        config: &Config,
+        food_lookup: &Lookup<FoodSource>,
+        origin: RealCoords,
+        range: u32,
+    ) -> Arc<Vec<Sighting>> {
+        let settings = self.refresh(config);
+        if let Some(sightings) = self.sightings.get(&(origin, range)) {
+            return sightings.clone();
+        }
+        let offsets = self.offsets(config, range);
+        let grid = Grid::from_config(config);
+        let mut seen = HashSet::new();
+        let mut sightings = Vec::new();
+        for &(dx, dy) in offsets.iter() {
+            let Some(entity) = grid
+                .offset(origin, dx, dy)
+                .and_then(|tile| food_lookup.entities.get(&tile))
+            else {
+                continue;
+            };
+            if settings.line_of_sight && !in_sight(&grid, food_lookup, origin, dx, dy) {
+                continue;
+            }
+            // on small wrapped maps the same tree may be in sight in two directions
+            if seen.insert(*entity) {
+                sightings.push(Sighting {
+                    entity: *entity,
+                    dx,
+                    dy,
+                });
+            }
+        }
+        let sightings = Arc::new(sightings);
+        self.sightings.insert((origin, range), sightings.clone());
+        sightings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shapes_should_cover_expected_tiles() {
+        assert_eq!(VisionShape::Cross.offsets(2).len(), 9);
+        assert_eq!(VisionShape::Manhattan.offsets(2).len(), 13);
+        assert_eq!(VisionShape::Chebyshev.offsets(2).len(), 25);
+        assert_eq!(VisionShape::Euclidean.offsets(2).len(), 13);
+        assert_eq!(VisionShape::Euclidean.offsets(3).len(), 29);
+        assert!(VisionShape::Chebyshev.contains(1, 1, 1));
+        assert!(!VisionShape::Cross.contains(1, 1, 1));
+    }
+
+    #[test]
+    fn nearest_tiles_should_come_first() {
+        let offsets = VisionShape::Chebyshev.offsets(3);
+        assert_eq!(offsets[0], (0, 0));
+        assert!(offsets
+            .windows(2)
+            .all(|w| w[0].0.pow(2) + w[0].1.pow(2) <= w[1].0.pow(2) + w[1].1.pow(2)));
+    }
+
+    #[test]
+    fn line_of_sight_should_cross_tiles_between() {
+        assert_eq!(line_of_sight(3, 0), vec![(1, 0), (2, 0)]);
+        assert_eq!(line_of_sight(-2, -2), vec![(-1, -1)]);
+        assert_eq!(line_of_sight(1, 1), vec![]);
+        assert_eq!(line_of_sight(0, 0), vec![]);
+        assert_eq!(line_of_sight(4, 2), vec![(1, 1), (2, 1), (3, 2)]);
+    }
+
+    #[quickcheck]
+    fn line_of_sight_should_make_single_steps(dx: i8, dy: i8) -> bool {
+        let (dx, dy) = (dx as i32, dy as i32);
+        let mut tiles = vec![(0, 0)];
+        tiles.extend(line_of_sight(dx, dy));
+        tiles.push((dx, dy));
+        (dx, dy) == (0, 0)
+            || tiles
+                .windows(2)
+                .all(|w| (w[0].0 - w[1].0).abs() <= 1 && (w[0].1 - w[1].1).abs() <= 1)
+    }
+}
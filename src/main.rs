@@ -16,6 +16,10 @@ mod rendering;
 mod stats;
 
 fn main() {
+    if std::env::args().any(|arg| arg == "--train") {
+        logic::learning::train();
+        return;
+    }
     App::new()
         .insert_resource(Performance::new(100))
         .add_plugins(
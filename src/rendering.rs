@@ -1,6 +1,7 @@
 mod camera;
 mod planet;
-mod tiles;
+mod territory;
+pub(crate) mod tiles;
 pub mod ui;
 
 use crate::stats;
@@ -19,12 +20,14 @@ impl Plugin for RenderingPlugin {
             .add_startup_system(tiles::randomize_tiles.in_base_set(StartupSet::PostStartup))
             .add_startup_system(camera::init_camera.in_base_set(StartupSet::PostStartup))
             .add_system(tiles::update_food_tiles)
+            .add_system(territory::territory_overlay_system)
             .insert_resource(ui::UiState {
                 open_settings_panel: ui::SettingsPanel::Game,
             })
             .add_system(ui::settings)
             .add_system(stats::ui::food_statistics)
             .add_system(stats::ui::money_statistics)
+            .add_system(stats::genetics::genetics_statistics)
             .add_system(planet::death_system)
             .add_system(planet::missing_sprite_setter_system)
             .add_system(planet::render_aging_system)
@@ -0,0 +1,53 @@
+use crate::debug::components::Performance;
+use bevy::prelude::*;
+use bevy_ecs_tilemap::prelude::*;
+use macros::measured;
+
+use crate::config::Config;
+use crate::logic::planet::TotalTicks;
+use crate::logic::tribes::Territory;
+use crate::logic::VirtualCoords;
+
+/// Golden angle spreads hues of consecutive tribes as far from each other as possible
+const HUE_STEP: f32 = 137.5;
+const MAX_TINT: f32 = 0.5;
+/// Strength of claims fades all the time, tints follow it only every this many frames
+const REFRESH_INTERVAL: u32 = 100;
+
+fn tribe_color(tribe: u32, strength: f32) -> Color {
+    let color = Color::hsl((tribe as f32 * HUE_STEP) % 360.0, 0.8, 0.5);
+    let tint = strength.clamp(0.0, 1.0) * MAX_TINT;
+    Color::rgb(
+        1.0 + (color.r() - 1.0) * tint,
+        1.0 + (color.g() - 1.0) * tint,
+        1.0 + (color.b() - 1.0) * tint,
+    )
+}
+
+/// Tints tiles claimed by tribes, the more firmly a tile is held the stronger the tint
+#[measured]
+pub fn territory_overlay_system(
+    mut tiles: Query<(&mut TileColor, &VirtualCoords), With<TilePos>>,
+    territory: Res<Territory>,
+    time: Res<TotalTicks>,
+    mut last_refresh: Local<u32>,
+    config: Res<Config>,
+) {
+    if !territory.is_changed()
+        && !config.is_changed()
+        && time.0.abs_diff(*last_refresh) < REFRESH_INTERVAL
+    {
+        return;
+    }
+    *last_refresh = time.0;
+    let show = config.ui.show_territories.value;
+    for (mut color, coords) in tiles.iter_mut() {
+        let new_color = match territory.claims.get(&coords.to_real(&config)) {
+            Some(claim) if show => tribe_color(claim.tribe, claim.strength),
+            _ => Color::WHITE,
+        };
+        if color.0 != new_color {
+            color.0 = new_color;
+        }
+    }
+}
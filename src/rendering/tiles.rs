@@ -2,9 +2,10 @@ use crate::debug::components::Performance;
 use bevy::prelude::*;
 use bevy_ecs_tilemap::prelude::*;
 use macros::measured;
-use rand::{thread_rng, Rng};
+use rand::thread_rng;
 
 use crate::logic::components::Lookup;
+use crate::logic::planet::random_food_source;
 use crate::logic::VirtualCoords;
 use crate::{
     config::Config,
@@ -35,52 +36,17 @@ pub fn randomize_tiles(
 ) {
     let mut random = thread_rng();
     for (entity, mut tile, coords) in query.iter_mut() {
-        let source;
-        let food_amount;
-        let sparsing_speed = 0.4;
-        let size = config.map.size_x.value as f32;
-        if random.gen_range(0.0..1.0)
-            < (size * config.map.apple_tree_tile_probability.value
-                - ((coords.x + coords.y) as f32) * sparsing_speed)
-                / size
-        {
-            tile.0 = random.gen_range(2..6);
-            source = FoodSource(FoodType::Apple);
-            food_amount = FoodAmount {
-                apples: tile.0 - FIRST_APPLE_TILE_INDEX,
-                oranges: 0,
-            };
-        } else if random.gen_range(0.0..1.0)
-            < (size * config.map.orange_tree_tile_probability.value
-                - (((config.map.size_x.value - coords.to_real(&config).x)
-                    + (config.map.size_y.value - coords.to_real(&config).y))
-                    as f32)
-                    * sparsing_speed)
-                / size
-        {
-            tile.0 = random.gen_range(6..10);
-            source = FoodSource(FoodType::Orange);
-            food_amount = FoodAmount {
-                apples: 0,
-                oranges: tile.0 - FIRST_ORANGE_TILE_INDEX,
-            };
-        } else {
+        let Some((source, food_amount)) = random_food_source(coords, &config, &mut random) else {
             tile.0 = 0;
-            // this will never happen
-            source = FoodSource(FoodType::Apple);
-            food_amount = FoodAmount {
-                apples: 0,
-                oranges: 0,
-            };
-        }
-        // if food_amount.apples == 0 && food_amount.oranges == 0 {
-        //     tile.0 = EMPTY_FOOD_TILE_INDEX;
-        // }
-        if (2..10).contains(&tile.0) {
-            commands.entity(entity).insert(source).insert(food_amount);
-            // insert entity to food_lookup using coords as key
-            food_lookup.entities.insert(coords.to_real(&config), entity);
-        }
+            continue;
+        };
+        tile.0 = match source.0 {
+            FoodType::Apple => food_amount.apples + FIRST_APPLE_TILE_INDEX,
+            FoodType::Orange => food_amount.oranges + FIRST_ORANGE_TILE_INDEX,
+        };
+        commands.entity(entity).insert(source).insert(food_amount);
+        // insert entity to food_lookup using coords as key
+        food_lookup.entities.insert(coords.to_real(&config), entity);
     }
     info!("Tiles were randomized");
 }
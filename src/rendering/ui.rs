@@ -11,6 +11,11 @@ use bevy_egui::{
 use egui::plot::{Line, PlotPoints};
 
 use crate::config::{Config, ConfigValue, CONFIG_PATH};
+use crate::logic::abilities::AbilityCurve;
+use crate::logic::goap::DecisionMaking;
+use crate::logic::mortality::MortalityModel;
+use crate::logic::thinkers::Thinkers;
+use crate::logic::vision::VisionShape;
 use crate::logic::GeometryType;
 
 #[derive(PartialEq, Eq, Display)]
@@ -19,6 +24,10 @@ pub enum SettingsPanel {
     Game,
     Map,
     Ai,
+    Abilities,
+    Social,
+    Disease,
+    Tribes,
 }
 
 #[derive(Resource)]
@@ -30,6 +39,7 @@ pub fn settings(
     mut egui_context: EguiContexts,
     mut config: ResMut<Config>,
     mut state: ResMut<UiState>,
+    thinkers: Res<Thinkers>,
 ) {
     egui::Window::new("Config").show(egui_context.ctx_mut(), |ui| {
         ui.collapsing("Instructions", |ui| {
@@ -43,6 +53,10 @@ pub fn settings(
             add_settings_panel(ui, &mut state.open_settings_panel, SettingsPanel::Camera);
             add_settings_panel(ui, &mut state.open_settings_panel, SettingsPanel::Map);
             add_settings_panel(ui, &mut state.open_settings_panel, SettingsPanel::Ai);
+            add_settings_panel(ui, &mut state.open_settings_panel, SettingsPanel::Abilities);
+            add_settings_panel(ui, &mut state.open_settings_panel, SettingsPanel::Social);
+            add_settings_panel(ui, &mut state.open_settings_panel, SettingsPanel::Disease);
+            add_settings_panel(ui, &mut state.open_settings_panel, SettingsPanel::Tribes);
             let space_left = ui.available_size() - egui::Vec2 { x: 45.0, y: 0.0 };
             ui.allocate_space(space_left);
             if ui.button("Save").clicked() {
@@ -63,14 +77,37 @@ pub fn settings(
                 draw_config_value(ui, &mut config.game.growth);
                 draw_config_value(ui, &mut config.game.hunger_increase);
                 draw_config_value(ui, &mut config.game.hunger_decrease);
+                draw_config_value(ui, &mut config.game.starvation_damage);
+                draw_config_value(ui, &mut config.game.health_recovery);
+                draw_config_value(ui, &mut config.game.weakness_threshold);
                 draw_config_value(ui, &mut config.game.starting_people);
                 draw_config_value(ui, &mut config.game.max_person_age);
+                draw_mortality_model(ui, &mut config.game.mortality_model);
+                draw_config_value(ui, &mut config.game.makeham_background);
+                draw_config_value(ui, &mut config.game.gompertz_initial);
+                draw_config_value(ui, &mut config.game.gompertz_growth);
+                draw_config_value(ui, &mut config.game.infant_mortality);
+                draw_life_table(ui, &mut config.game.life_table);
+                draw_config_value(ui, &mut config.game.min_fertile_age_male);
+                draw_config_value(ui, &mut config.game.max_fertile_age_male);
+                draw_config_value(ui, &mut config.game.min_fertile_age_female);
+                draw_config_value(ui, &mut config.game.max_fertile_age_female);
                 draw_config_value(ui, &mut config.game.food_for_baby);
+                draw_config_value(ui, &mut config.game.conception_probability);
+                draw_config_value(ui, &mut config.game.gestation_length);
+                draw_config_value(ui, &mut config.game.pregnancy_hunger_increase);
+                draw_config_value(ui, &mut config.game.birth_cooldown);
+                draw_config_value(ui, &mut config.game.twins_probability);
+                draw_config_value(ui, &mut config.game.child_food_reserve);
+                draw_config_value(ui, &mut config.game.guardian_distance);
                 draw_config_value(ui, &mut config.game.person_ttl);
                 draw_config_value(ui, &mut config.game.year_length);
                 draw_config_value(ui, &mut config.game.growing_season_length);
                 draw_bool_config_value(ui, &mut config.game.death_lottery);
                 draw_bool_config_value(ui, &mut config.game.trade_allowed);
+                draw_config_value(ui, &mut config.game.initial_trait_variation);
+                draw_config_value(ui, &mut config.game.mutation_probability);
+                draw_config_value(ui, &mut config.game.mutation_strength);
             }),
             SettingsPanel::Map => add_options_grid(ui, |ui| {
                 draw_config_value(ui, &mut config.map.size_x);
@@ -83,12 +120,73 @@ pub fn settings(
                 draw_config_value(ui, &mut config.ai.food_amount_goal);
                 draw_config_value(ui, &mut config.ai.food_amount_threshold);
                 draw_config_value(ui, &mut config.ai.vision_range);
+                draw_vision_shape(ui, &mut config.ai.vision_shape);
+                draw_bool_config_value(ui, &mut config.ai.line_of_sight);
+                draw_config_value(ui, &mut config.ai.occupied_tile_cost);
+                draw_config_value(ui, &mut config.ai.max_path_search);
+                draw_config_value(ui, &mut config.ai.trade_need_ratio);
+                draw_config_value(ui, &mut config.ai.season_horizon);
+                draw_config_value(ui, &mut config.ai.stockpile_factor);
+                draw_thinker(ui, &mut config.ai.thinker, &thinkers);
+                draw_decision_making(ui, &mut config.ai.decision_making);
+                draw_bool_config_value(ui, &mut config.ai.personalities);
+                draw_config_value(ui, &mut config.ai.max_plan_length);
+                draw_config_value(ui, &mut config.ai.learning_rate);
+                draw_config_value(ui, &mut config.ai.discount);
+                draw_config_value(ui, &mut config.ai.exploration);
+                draw_config_value(ui, &mut config.ai.training_episodes);
+                draw_config_value(ui, &mut config.ai.episode_length);
+            }),
+            SettingsPanel::Abilities => add_options_grid(ui, |ui| {
+                ui.label("");
+                ui.label("Child / adult / old");
+                ui.end_row();
+                draw_ability_curve(ui, &mut config.abilities.movement_speed);
+                draw_ability_curve(ui, &mut config.abilities.vision_range);
+                draw_ability_curve(ui, &mut config.abilities.foraging_yield);
+                draw_ability_curve(ui, &mut config.abilities.trading_skill);
+            }),
+            SettingsPanel::Social => add_options_grid(ui, |ui| {
+                draw_config_value(ui, &mut config.social.max_partners);
+                draw_config_value(ui, &mut config.social.choosiness);
+                draw_config_value(ui, &mut config.social.wealth_weight);
+                draw_config_value(ui, &mut config.social.age_weight);
+                draw_config_value(ui, &mut config.social.traits_weight);
+                draw_config_value(ui, &mut config.social.trust_weight);
+                draw_config_value(ui, &mut config.social.household_sharing);
+                draw_bool_config_value(ui, &mut config.social.reproduce_only_with_partner);
+                draw_config_value(ui, &mut config.social.trust_per_interaction);
+                draw_config_value(ui, &mut config.social.trust_per_trade);
+                draw_config_value(ui, &mut config.social.trust_decay);
+                draw_config_value(ui, &mut config.social.gossip_share_count);
+                draw_config_value(ui, &mut config.social.gossip_reliability);
+                draw_config_value(ui, &mut config.social.min_reliability);
+                draw_config_value(ui, &mut config.social.min_confidence);
+                draw_config_value(ui, &mut config.social.knowledge_capacity);
+                draw_config_value(ui, &mut config.social.memory_strength);
+            }),
+            SettingsPanel::Disease => add_options_grid(ui, |ui| {
+                draw_config_value(ui, &mut config.disease.outbreak_probability);
+                draw_config_value(ui, &mut config.disease.transmission_probability);
+                draw_config_value(ui, &mut config.disease.incubation_period);
+                draw_config_value(ui, &mut config.disease.infectious_period);
+                draw_config_value(ui, &mut config.disease.sickness_hunger_increase);
+                draw_config_value(ui, &mut config.disease.sickness_mortality);
+                draw_config_value(ui, &mut config.disease.immunity_duration);
+            }),
+            SettingsPanel::Tribes => add_options_grid(ui, |ui| {
+                draw_config_value(ui, &mut config.tribes.join_trust);
+                draw_config_value(ui, &mut config.tribes.kinship_trust);
+                draw_config_value(ui, &mut config.tribes.sharing);
+                draw_config_value(ui, &mut config.tribes.territory_claim);
+                draw_config_value(ui, &mut config.tribes.territory_decay);
+                draw_config_value(ui, &mut config.tribes.exclusion_threshold);
             }),
         }
     });
 }
 
-fn draw_bool_config_value(ui: &mut Ui, value: &mut ConfigValue<bool>) {
+pub fn draw_bool_config_value(ui: &mut Ui, value: &mut ConfigValue<bool>) {
     let label = ui.label(&value.name);
     if let Some(hint) = &value.description {
         label.on_hover_text(hint);
@@ -147,6 +245,109 @@ fn draw_geometry_type(ui: &mut Ui, value: &mut ConfigValue<GeometryType>) {
     ui.end_row();
 }
 
+fn draw_mortality_model(ui: &mut Ui, value: &mut ConfigValue<MortalityModel>) {
+    let label = ui.label(&value.name);
+    if let Some(hint) = &value.description {
+        label.on_hover_text(hint);
+    }
+    egui::ComboBox::from_id_source("mortality_model")
+        .selected_text(value.value.to_string())
+        .show_ui(ui, |ui| {
+            ui.set_min_width(120.0);
+            ui.selectable_value(&mut value.value, MortalityModel::HardLimit, "Hard limit");
+            ui.selectable_value(
+                &mut value.value,
+                MortalityModel::GompertzMakeham,
+                "Gompertz-Makeham",
+            );
+            ui.selectable_value(&mut value.value, MortalityModel::LifeTable, "Life table");
+        });
+    ui.end_row();
+}
+
+fn draw_vision_shape(ui: &mut Ui, value: &mut ConfigValue<VisionShape>) {
+    let label = ui.label(&value.name);
+    if let Some(hint) = &value.description {
+        label.on_hover_text(hint);
+    }
+    egui::ComboBox::from_id_source("vision_shape")
+        .selected_text(value.value.to_string())
+        .show_ui(ui, |ui| {
+            ui.set_min_width(120.0);
+            ui.selectable_value(&mut value.value, VisionShape::Cross, "Cross");
+            ui.selectable_value(&mut value.value, VisionShape::Manhattan, "Manhattan");
+            ui.selectable_value(&mut value.value, VisionShape::Chebyshev, "Chebyshev");
+            ui.selectable_value(&mut value.value, VisionShape::Euclidean, "Euclidean");
+        });
+    ui.end_row();
+}
+
+fn draw_decision_making(ui: &mut Ui, value: &mut ConfigValue<DecisionMaking>) {
+    let label = ui.label(&value.name);
+    if let Some(hint) = &value.description {
+        label.on_hover_text(hint);
+    }
+    egui::ComboBox::from_id_source("decision_making")
+        .selected_text(value.value.to_string())
+        .show_ui(ui, |ui| {
+            ui.set_min_width(120.0);
+            ui.selectable_value(&mut value.value, DecisionMaking::Utility, "Utility");
+            ui.selectable_value(&mut value.value, DecisionMaking::Goap, "Goap");
+            ui.selectable_value(&mut value.value, DecisionMaking::Learned, "Learned");
+        });
+    ui.end_row();
+}
+
+fn draw_thinker(ui: &mut Ui, value: &mut ConfigValue<String>, thinkers: &Thinkers) {
+    let label = ui.label(&value.name);
+    if let Some(hint) = &value.description {
+        label.on_hover_text(hint);
+    }
+    egui::ComboBox::from_id_source("thinker")
+        .selected_text(value.value.as_str())
+        .show_ui(ui, |ui| {
+            ui.set_min_width(120.0);
+            for name in thinkers.thinkers.keys() {
+                ui.selectable_value(&mut value.value, name.clone(), name);
+            }
+        });
+    ui.end_row();
+}
+
+fn draw_ability_curve(ui: &mut Ui, value: &mut ConfigValue<AbilityCurve>) {
+    let label = ui.label(&value.name);
+    if let Some(hint) = &value.description {
+        label.on_hover_text(hint);
+    }
+    ui.horizontal(|ui| {
+        let curve = &mut value.value;
+        for ability in [&mut curve.child, &mut curve.adult, &mut curve.old] {
+            ui.add(
+                egui::DragValue::new(ability)
+                    .speed(0.01)
+                    .clamp_range(0.0..=10.0),
+            );
+        }
+    });
+    ui.end_row();
+}
+
+fn draw_life_table(ui: &mut Ui, value: &mut ConfigValue<Vec<f32>>) {
+    let label = ui.label(&value.name);
+    if let Some(hint) = &value.description {
+        label.on_hover_text(hint);
+    }
+    ui.collapsing("Yearly probabilities", |ui| {
+        for (age, probability) in value.value.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("{}", age));
+                ui.add(egui::Slider::new(probability, 0.0..=1.0));
+            });
+        }
+    });
+    ui.end_row();
+}
+
 pub fn create_plot_line(name: &str, values: &[u32]) -> Line {
     let stats: PlotPoints = values
         .iter()
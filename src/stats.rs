@@ -1,5 +1,18 @@
 pub mod components;
+mod decisions;
+pub mod disease;
+mod dynasty;
 mod economy;
+pub mod genetics;
+mod goals;
+pub mod health;
+pub mod knowledge;
+pub mod mortality;
+mod partnership;
+pub mod personalities;
+mod relationships;
+pub mod seasons;
+pub mod tribes;
 pub mod ui;
 
 use bevy::prelude::{App, Plugin};
@@ -15,12 +28,47 @@ impl Plugin for StatsPlugin {
             orange_history_people: vec![],
             people_history: vec![],
             trade_history: vec![],
+            genome_history: vec![],
+            conceptions_history: vec![],
+            births_history: vec![],
+            partner_matches: vec![],
+            partnership_durations: vec![],
+            sir_history: vec![],
+            infections_history: vec![],
+            child_deaths_history: vec![],
+            orphan_deaths: 0,
+            mortality_history: vec![],
+            current_mortality: mortality::YearMortality::default(),
+            health_history: vec![],
+            tribes_history: vec![],
+            knowledge_history: vec![],
+            gossip_history: vec![],
+            personality_history: vec![],
+            migration_history: vec![],
             current_food: 0,
             current_apples: 0,
             current_oranges: 0,
             current_people: 0,
         })
         .add_system(economy::food_statistics)
-        .add_system(ui::stats_window);
+        .add_system(genetics::genome_statistics)
+        .add_system(disease::disease_statistics)
+        .add_system(mortality::mortality_statistics)
+        .add_system(health::health_statistics)
+        .add_system(tribes::tribes_statistics)
+        .add_system(knowledge::knowledge_statistics)
+        .add_system(personalities::personality_statistics)
+        .add_system(seasons::migration_statistics)
+        .add_system(ui::stats_window)
+        .add_system(dynasty::family_tree_window)
+        .add_system(dynasty::dynasty_window)
+        .add_system(partnership::partnership_window)
+        .add_system(mortality::mortality_window)
+        .add_system(relationships::relationships_window)
+        .add_system(tribes::tribes_window)
+        .add_system(goals::goals_window)
+        .add_system(decisions::decisions_window)
+        .add_system(personalities::personalities_window)
+        .add_system(seasons::seasons_window);
     }
 }
@@ -1 +1 @@
-pub use super::economy::{Statistics, Transaction};
+pub use super::economy::{PartnerMatch, Statistics, Transaction};
@@ -0,0 +1,78 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use macros::measured;
+
+use crate::debug::components::Performance;
+use crate::input::selection::SelectedPerson;
+use crate::logic::components::Name;
+use crate::logic::decisions::Decisions;
+
+const DESTINATIONS_SHOWN: usize = 10;
+
+/// Scores, actions and candidate destinations behind the behaviour of the selected person
+#[measured]
+pub fn decisions_window(
+    mut egui_context: EguiContexts,
+    selected: Res<SelectedPerson>,
+    people: Query<(&Decisions, Option<&Name>)>,
+) {
+    let Some((decisions, name)) = selected.0.and_then(|person| people.get(person).ok()) else {
+        return;
+    };
+    egui::Window::new("AI inspector").show(egui_context.ctx_mut(), |ui| {
+        if let Some(name) = name {
+            ui.label(format!("Decisions of {}", name.0));
+        }
+        ui.label(format!(
+            "Chosen action: {}",
+            decisions.current().unwrap_or("None")
+        ));
+        ui.separator();
+        egui::Grid::new("decision_scores")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Scorer");
+                ui.label("Score");
+                ui.end_row();
+                for (scorer, score) in decisions.scores.iter() {
+                    ui.label(scorer);
+                    ui.label(format!("{:.3}", score));
+                    ui.end_row();
+                }
+            });
+        ui.separator();
+        egui::Grid::new("decision_actions")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Tick");
+                ui.label("Action");
+                ui.end_row();
+                for (tick, action) in decisions.actions.iter() {
+                    ui.label(format!("{}", tick));
+                    ui.label(action);
+                    ui.end_row();
+                }
+            });
+        ui.separator();
+        let mut destinations = decisions.destinations.clone();
+        destinations.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        ui.label(format!(
+            "Destinations considered by the last move: {}",
+            destinations.len()
+        ));
+        egui::Grid::new("decision_destinations")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("X");
+                ui.label("Y");
+                ui.label("Score");
+                ui.end_row();
+                for (coords, score) in destinations.iter().take(DESTINATIONS_SHOWN) {
+                    ui.label(format!("{}", coords.x));
+                    ui.label(format!("{}", coords.y));
+                    ui.label(format!("{:.3}", score));
+                    ui.end_row();
+                }
+            });
+    });
+}
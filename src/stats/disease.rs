@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+use macros::measured;
+
+use crate::debug::components::Performance;
+use crate::logic::components::Person;
+use crate::logic::disease::{Exposed, Immune, Infectious};
+use crate::stats::economy::Statistics;
+
+/// Number of people in each compartment of the SIR model, exposed people are counted separately
+#[derive(Clone, Copy, Default)]
+pub struct SirCounts {
+    pub susceptible: u32,
+    pub exposed: u32,
+    pub infectious: u32,
+    pub recovered: u32,
+}
+
+#[measured]
+#[allow(clippy::type_complexity)]
+pub fn disease_statistics(
+    people: Query<(Option<&Exposed>, Option<&Infectious>, Option<&Immune>), With<Person>>,
+    mut stats: ResMut<Statistics>,
+) {
+    let mut counts = SirCounts::default();
+    for compartments in people.iter() {
+        match compartments {
+            (Some(_), _, _) => counts.exposed += 1,
+            (_, Some(_), _) => counts.infectious += 1,
+            (_, _, Some(_)) => counts.recovered += 1,
+            _ => counts.susceptible += 1,
+        }
+    }
+    stats.sir_history.push(counts);
+}
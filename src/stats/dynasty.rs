@@ -0,0 +1,229 @@
+use bevy::prelude::*;
+use bevy_egui::egui::Ui;
+use bevy_egui::{egui, EguiContexts};
+use std::collections::HashMap;
+
+use crate::config::Config;
+use crate::input::selection::SelectedPerson;
+use crate::logic::components::{FoodAmount, Person};
+use crate::logic::lineage::{FamilyName, Genealogy};
+
+const LARGEST_FAMILIES_SHOWN: usize = 10;
+const ANCESTOR_GENERATIONS: u32 = 3;
+const DESCENDANT_GENERATIONS: u32 = 4;
+
+#[derive(Default)]
+struct DynastySummary {
+    living: u32,
+    ever_lived: u32,
+    wealth: u32,
+    descendants: usize,
+}
+
+pub fn family_tree_window(
+    mut egui_context: EguiContexts,
+    mut selected: ResMut<SelectedPerson>,
+    genealogy: Res<Genealogy>,
+    config: Res<Config>,
+) {
+    if let Some(person) = selected.0 {
+        let mut open = true;
+        let mut newly_selected = None;
+        egui::Window::new("Family tree")
+            .open(&mut open)
+            .show(egui_context.ctx_mut(), |ui| {
+                if !genealogy.records.contains_key(&person) {
+                    ui.label("Selected person is not in the archive yet");
+                    return;
+                }
+                draw_person_summary(ui, &genealogy, &config, person);
+                ui.separator();
+                ui.collapsing("Ancestors", |ui| {
+                    draw_ancestors(ui, &genealogy, person, 1, &mut newly_selected);
+                });
+                ui.collapsing("Descendants", |ui| {
+                    draw_descendants(ui, &genealogy, person, 1, &mut newly_selected);
+                });
+            });
+        if !open {
+            selected.0 = None;
+        } else if newly_selected.is_some() {
+            selected.0 = newly_selected;
+        }
+    }
+}
+
+fn draw_person_summary(ui: &mut Ui, genealogy: &Genealogy, config: &Config, person: Entity) {
+    let record = &genealogy.records[&person];
+    ui.heading(&record.name);
+    ui.label(format!(
+        "Sex: {}",
+        if record.male { "male" } else { "female" }
+    ));
+    let year_length = config.game.year_length.value.max(1);
+    ui.label(format!(
+        "Born in year {}",
+        record.born.div_euclid(year_length as i64)
+    ));
+    if let Some(died) = record.died {
+        ui.label(format!(
+            "Died in year {} aged {:.1} years",
+            died / year_length,
+            (died as i64 - record.born) as f32 / year_length as f32
+        ));
+    }
+    ui.label(format!("Children: {}", record.children.len()));
+}
+
+fn draw_person_link(
+    ui: &mut Ui,
+    genealogy: &Genealogy,
+    person: Entity,
+    newly_selected: &mut Option<Entity>,
+) {
+    if let Some(record) = genealogy.records.get(&person) {
+        let label = if record.died.is_some() {
+            format!("{} †", record.name)
+        } else {
+            record.name.clone()
+        };
+        if ui.link(label).clicked() {
+            *newly_selected = Some(person);
+        }
+    } else {
+        ui.label("unknown");
+    }
+}
+
+fn draw_ancestors(
+    ui: &mut Ui,
+    genealogy: &Genealogy,
+    person: Entity,
+    generation: u32,
+    newly_selected: &mut Option<Entity>,
+) {
+    let record = &genealogy.records[&person];
+    if let (Some(father), Some(mother)) = (record.father, record.mother) {
+        for (role, parent) in [("Father", father), ("Mother", mother)] {
+            ui.horizontal(|ui| {
+                ui.label(format!("{}:", role));
+                draw_person_link(ui, genealogy, parent, newly_selected);
+            });
+            if generation < ANCESTOR_GENERATIONS && genealogy.records.contains_key(&parent) {
+                ui.indent(parent, |ui| {
+                    draw_ancestors(ui, genealogy, parent, generation + 1, newly_selected);
+                });
+            }
+        }
+    } else {
+        ui.label("Founder of the family");
+    }
+}
+
+fn draw_descendants(
+    ui: &mut Ui,
+    genealogy: &Genealogy,
+    person: Entity,
+    generation: u32,
+    newly_selected: &mut Option<Entity>,
+) {
+    for child in genealogy.records[&person].children.iter() {
+        let grandchildren = genealogy
+            .records
+            .get(child)
+            .map(|record| record.children.len())
+            .unwrap_or(0);
+        if grandchildren == 0 {
+            draw_person_link(ui, genealogy, *child, newly_selected);
+        } else {
+            ui.horizontal(|ui| {
+                draw_person_link(ui, genealogy, *child, newly_selected);
+                ui.label(format!("({} children)", grandchildren));
+            });
+            // later generations are shown by selecting one of their ancestors
+            if generation < DESCENDANT_GENERATIONS {
+                ui.indent(child, |ui| {
+                    draw_descendants(ui, genealogy, *child, generation + 1, newly_selected);
+                });
+            }
+        }
+    }
+}
+
+pub fn dynasty_window(
+    mut egui_context: EguiContexts,
+    genealogy: Res<Genealogy>,
+    living: Query<(&FamilyName, &FoodAmount), With<Person>>,
+    mut descendants: Local<HashMap<Entity, usize>>,
+) {
+    // counting goes through the whole archive, so it is done only when somebody is born or dies
+    if genealogy.is_changed() {
+        *descendants = genealogy.descendant_counts();
+    }
+    egui::Window::new("Dynasties").show(egui_context.ctx_mut(), |ui| {
+        let mut dynasties: HashMap<&str, DynastySummary> = HashMap::new();
+        for (family_name, food) in living.iter() {
+            let dynasty = dynasties.entry(family_name.0.as_str()).or_default();
+            dynasty.living += 1;
+            dynasty.wealth += food.apples + food.oranges;
+        }
+        for (person, record) in genealogy.records.iter() {
+            let dynasty = dynasties.entry(record.family_name.as_str()).or_default();
+            dynasty.ever_lived += 1;
+            dynasty.descendants += descendants.get(person).copied().unwrap_or(0);
+        }
+        let completed_lives = genealogy
+            .records
+            .iter()
+            .filter(|(_, record)| record.died.is_some())
+            .collect::<Vec<_>>();
+        ui.label(format!(
+            "Living families: {}",
+            dynasties.values().filter(|d| d.living > 0).count()
+        ));
+        ui.label(format!("People in archive: {}", genealogy.records.len()));
+        if completed_lives.is_empty() {
+            ui.label("Average descendants of the deceased: -");
+        } else {
+            ui.label(format!(
+                "Average descendants of the deceased: {:.2}",
+                completed_lives
+                    .iter()
+                    .map(|(person, _)| descendants.get(person).copied().unwrap_or(0))
+                    .sum::<usize>() as f32
+                    / completed_lives.len() as f32
+            ));
+        }
+        ui.separator();
+        let mut largest = dynasties
+            .iter()
+            .filter(|(_, d)| d.living > 0)
+            .collect::<Vec<_>>();
+        largest.sort_by(|a, b| b.1.living.cmp(&a.1.living).then(a.0.cmp(b.0)));
+        egui::Grid::new("dynasties_grid")
+            .num_columns(5)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("Family");
+                ui.strong("Living");
+                ui.strong("Wealth");
+                ui.strong("Wealth per member");
+                ui.strong("Avg descendants");
+                ui.end_row();
+                for (name, dynasty) in largest.iter().take(LARGEST_FAMILIES_SHOWN) {
+                    ui.label(**name);
+                    ui.label(dynasty.living.to_string());
+                    ui.label(dynasty.wealth.to_string());
+                    ui.label(format!(
+                        "{:.1}",
+                        dynasty.wealth as f32 / dynasty.living as f32
+                    ));
+                    ui.label(format!(
+                        "{:.2}",
+                        dynasty.descendants as f32 / dynasty.ever_lived.max(1) as f32
+                    ));
+                    ui.end_row();
+                }
+            });
+    });
+}
@@ -2,13 +2,22 @@ use bevy::prelude::*;
 
 use crate::debug::components::Performance;
 use crate::logic::components::{FoodAmount, FoodSource, Person};
+use crate::logic::genetics::TRAIT_COUNT;
+use crate::stats::disease::SirCounts;
+use crate::stats::genetics::TraitSummary;
+use crate::stats::health::HealthSummary;
+use crate::stats::knowledge::KnowledgeSummary;
+use crate::stats::mortality::YearMortality;
+use crate::stats::personalities::ProfileSummary;
+use crate::stats::seasons::MigrationSummary;
+use crate::stats::tribes::TribeSummary;
 use macros::measured;
 
 pub struct Transaction {
     pub apples: u32,
     pub oranges: u32,
 }
-#[derive(Resource)]
+#[derive(Resource, Default)]
 pub struct Statistics {
     pub apple_history_sources: Vec<u32>,
     pub orange_history_sources: Vec<u32>,
@@ -20,6 +29,33 @@ pub struct Statistics {
     pub current_oranges: u32,
     pub current_people: u32,
     pub trade_history: Vec<Vec<Transaction>>,
+    pub genome_history: Vec<[TraitSummary; TRAIT_COUNT]>,
+    pub conceptions_history: Vec<u32>,
+    pub births_history: Vec<u32>,
+    pub partner_matches: Vec<PartnerMatch>,
+    pub partnership_durations: Vec<u32>,
+    pub sir_history: Vec<SirCounts>,
+    pub infections_history: Vec<u32>,
+    pub child_deaths_history: Vec<u32>,
+    pub orphan_deaths: u32,
+    pub mortality_history: Vec<YearMortality>,
+    pub current_mortality: YearMortality,
+    pub health_history: Vec<HealthSummary>,
+    pub tribes_history: Vec<TribeSummary>,
+    pub knowledge_history: Vec<KnowledgeSummary>,
+    /// Pieces of information learned from neighbours in each frame
+    pub gossip_history: Vec<u32>,
+    /// Summaries of personality profiles in the order of the loaded profiles
+    pub personality_history: Vec<Vec<ProfileSummary>>,
+    pub migration_history: Vec<MigrationSummary>,
+}
+
+/// Wealth and age of both partners at the moment they became a couple
+pub struct PartnerMatch {
+    pub male_wealth: f32,
+    pub female_wealth: f32,
+    pub male_age: f32,
+    pub female_age: f32,
 }
 
 #[measured]
@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+use bevy_egui::egui::plot::{Corner, Legend, Plot};
+use bevy_egui::egui::Ui;
+use bevy_egui::{egui, EguiContexts};
+use macros::measured;
+
+use crate::config::Config;
+use crate::debug::components::Performance;
+use crate::logic::components::Person;
+use crate::logic::genetics::{Genome, TRAIT_COUNT};
+use crate::rendering::ui::create_plot_line_f64;
+use crate::stats::economy::Statistics;
+
+#[derive(Clone, Copy, Default)]
+pub struct TraitSummary {
+    pub mean: f64,
+    pub variance: f64,
+}
+
+#[measured]
+pub fn genome_statistics(genomes: Query<&Genome, With<Person>>, mut stats: ResMut<Statistics>) {
+    let mut summaries = [TraitSummary::default(); TRAIT_COUNT];
+    for (index, summary) in summaries.iter_mut().enumerate() {
+        let values = genomes
+            .iter()
+            .map(|genome| genome.traits()[index].1 as f64)
+            .collect::<Vec<_>>();
+        (summary.mean, summary.variance) = mean_and_variance(&values);
+    }
+    stats.genome_history.push(summaries);
+}
+
+fn mean_and_variance(values: &[f64]) -> (f64, f64) {
+    if values.is_empty() {
+        return (0.0, 0.0);
+    }
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    let variance =
+        values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / values.len() as f64;
+    (mean, variance)
+}
+
+#[measured]
+pub fn genetics_statistics(
+    mut egui_context: EguiContexts,
+    stats: Res<Statistics>,
+    config: Res<Config>,
+) {
+    egui::Window::new("Genetics").show(egui_context.ctx_mut(), |ui| {
+        ui.label("Trait means over time");
+        plot_traits(&stats, &config, ui, "trait_means", |summary| summary.mean);
+        ui.label("Trait variances over time");
+        plot_traits(&stats, &config, ui, "trait_variances", |summary| {
+            summary.variance
+        });
+    });
+}
+
+fn plot_traits(
+    stats: &Statistics,
+    config: &Config,
+    ui: &mut Ui,
+    id: &str,
+    value: impl Fn(&TraitSummary) -> f64,
+) {
+    Plot::new(id)
+        .view_aspect(2.0)
+        .legend(Legend {
+            position: Corner::LeftTop,
+            ..default()
+        })
+        .show(ui, |plot_ui| {
+            let history = &stats.genome_history;
+            let start = history
+                .len()
+                .saturating_sub(config.ui.plot_time_range.value);
+            for (index, (name, _)) in Genome::default().traits().iter().enumerate() {
+                let values = history[start..]
+                    .iter()
+                    .map(|summaries| value(&summaries[index]))
+                    .collect::<Vec<_>>();
+                plot_ui.line(create_plot_line_f64(name, &values));
+            }
+        });
+}
@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use macros::measured;
+
+use crate::debug::components::Performance;
+use crate::input::selection::SelectedPerson;
+use crate::logic::goap::{Goal, Plan};
+
+/// People planning with GOAP grouped by the goal they pursue, and the plan of the selected person
+#[measured]
+pub fn goals_window(
+    mut egui_context: EguiContexts,
+    selected: Res<SelectedPerson>,
+    plans: Query<&Plan>,
+) {
+    if plans.is_empty() {
+        return;
+    }
+    egui::Window::new("Goals").show(egui_context.ctx_mut(), |ui| {
+        egui::Grid::new("goals").striped(true).show(ui, |ui| {
+            ui.label("Goal");
+            ui.label("People");
+            ui.end_row();
+            for goal in [Goal::NotStarving, Goal::Reproduce, Goal::AccumulateWealth] {
+                ui.label(goal.to_string());
+                ui.label(format!(
+                    "{}",
+                    plans.iter().filter(|plan| plan.goal == Some(goal)).count()
+                ));
+                ui.end_row();
+            }
+            ui.label("None");
+            ui.label(format!(
+                "{}",
+                plans.iter().filter(|plan| plan.goal.is_none()).count()
+            ));
+            ui.end_row();
+        });
+        if let Some(plan) = selected.0.and_then(|person| plans.get(person).ok()) {
+            ui.separator();
+            match plan.goal {
+                Some(goal) => {
+                    let steps = plan
+                        .actions
+                        .iter()
+                        .map(|action| action.to_string())
+                        .collect::<Vec<_>>();
+                    ui.label(format!("Selected person wants {}", goal));
+                    ui.label(format!("Plan: {}", steps.join(", ")));
+                }
+                None => {
+                    ui.label("Selected person has nothing to do");
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+use macros::measured;
+
+use crate::config::Config;
+use crate::debug::components::Performance;
+use crate::logic::components::{Hunger, Person};
+use crate::logic::health::{is_starving, Health};
+use crate::stats::economy::Statistics;
+
+#[derive(Clone, Copy, Default)]
+pub struct HealthSummary {
+    pub average: f64,
+    pub weak: u32,
+    pub starving: u32,
+}
+
+#[measured]
+pub fn health_statistics(
+    people: Query<(&Health, &Hunger), With<Person>>,
+    config: Res<Config>,
+    mut stats: ResMut<Statistics>,
+) {
+    let mut summary = HealthSummary::default();
+    let mut people_count = 0;
+    for (health, hunger) in people.iter() {
+        people_count += 1;
+        summary.average += health.0 as f64;
+        if health.is_weak(&config) {
+            summary.weak += 1;
+        }
+        if is_starving(hunger) {
+            summary.starving += 1;
+        }
+    }
+    if people_count > 0 {
+        summary.average /= people_count as f64;
+    }
+    stats.health_history.push(summary);
+}
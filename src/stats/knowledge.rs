@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+use macros::measured;
+
+use crate::debug::components::Performance;
+use crate::logic::components::Person;
+use crate::logic::knowledge::Knowledge;
+use crate::logic::planet::TotalTicks;
+use crate::stats::economy::Statistics;
+
+#[derive(Clone, Copy, Default)]
+pub struct KnowledgeSummary {
+    /// Food sources known by an average person
+    pub average: f64,
+    pub largest: u32,
+    /// Frames since the remembered food sources were seen, averaged over all memories
+    pub average_age: f64,
+    /// People who remember the price of their last trade
+    pub price_memories: u32,
+    /// Oranges per apple remembered by an average person who remembers a price
+    pub remembered_price: f64,
+}
+
+#[measured]
+pub fn knowledge_statistics(
+    people: Query<&Knowledge, With<Person>>,
+    time: Res<TotalTicks>,
+    mut stats: ResMut<Statistics>,
+) {
+    let mut summary = KnowledgeSummary::default();
+    let mut people_count = 0;
+    let mut memories = 0;
+    for knowledge in people.iter() {
+        people_count += 1;
+        summary.average += knowledge.infos.len() as f64;
+        summary.largest = summary.largest.max(knowledge.infos.len() as u32);
+        for info in knowledge.infos.iter() {
+            memories += 1;
+            summary.average_age += time.0.saturating_sub(info.observed) as f64;
+        }
+        if let Some(price) = knowledge.last_price {
+            summary.price_memories += 1;
+            summary.remembered_price += price.oranges_per_apple as f64;
+        }
+    }
+    if people_count > 0 {
+        summary.average /= people_count as f64;
+    }
+    if memories > 0 {
+        summary.average_age /= memories as f64;
+    }
+    if summary.price_memories > 0 {
+        summary.remembered_price /= summary.price_memories as f64;
+    }
+    stats.knowledge_history.push(summary);
+}
@@ -0,0 +1,213 @@
+use bevy::prelude::*;
+use bevy_egui::egui::plot::{Bar, BarChart, Corner, Legend, Plot};
+use bevy_egui::egui::Ui;
+use bevy_egui::{egui, EguiContexts};
+use macros::measured;
+
+use crate::config::Config;
+use crate::debug::components::Performance;
+use crate::logic::components::{Age, Dead, Person};
+use crate::logic::people::{AgeAtDeath, CauseOfDeath, CAUSE_OF_DEATH_COUNT};
+use crate::logic::planet::TotalTicks;
+use crate::stats::economy::Statistics;
+
+const YEARS_IN_TABLE: usize = 10;
+const YEARS_IN_PLOT: usize = 50;
+
+/// Deaths and time lived by everyone during one year, ages are counted in whole years
+#[derive(Clone, Default)]
+pub struct YearMortality {
+    pub deaths: [u32; CAUSE_OF_DEATH_COUNT],
+    pub deaths_by_age: Vec<u32>,
+    /// Ticks lived by people of given age, summed over all people
+    pub exposure_by_age: Vec<u32>,
+}
+
+impl YearMortality {
+    pub fn total_deaths(&self) -> u32 {
+        self.deaths.iter().sum()
+    }
+
+    pub fn life_expectancy(&self, year_length: u32) -> Option<f64> {
+        let exposure_years = self
+            .exposure_by_age
+            .iter()
+            .map(|ticks| *ticks as f64 / year_length as f64)
+            .collect::<Vec<_>>();
+        life_expectancy(&self.deaths_by_age, &exposure_years)
+    }
+}
+
+#[measured]
+pub fn mortality_statistics(
+    died: Query<(&CauseOfDeath, &AgeAtDeath), Added<Dead>>,
+    living: Query<&Age, With<Person>>,
+    time: Res<TotalTicks>,
+    config: Res<Config>,
+    mut stats: ResMut<Statistics>,
+) {
+    let year_length = config.game.year_length.value.max(1);
+    let current = &mut stats.current_mortality;
+    for (cause, age) in died.iter() {
+        current.deaths[cause.index()] += 1;
+        increment(&mut current.deaths_by_age, (age.0 / year_length) as usize);
+    }
+    for age in living.iter() {
+        increment(&mut current.exposure_by_age, (age.0 / year_length) as usize);
+    }
+    if time.0 > 0 && time.0.is_multiple_of(year_length) {
+        let finished = std::mem::take(&mut stats.current_mortality);
+        stats.mortality_history.push(finished);
+    }
+}
+
+fn increment(values: &mut Vec<u32>, index: usize) {
+    if values.len() <= index {
+        values.resize(index + 1, 0);
+    }
+    values[index] += 1;
+}
+
+/// Period life expectancy at birth. Death rates of one year are applied to a synthetic cohort,
+/// people dying during a year of age are assumed to live half of it. Nobody survives the oldest
+/// age that was observed.
+pub fn life_expectancy(deaths_by_age: &[u32], exposure_years_by_age: &[f64]) -> Option<f64> {
+    let ages = deaths_by_age.len().max(exposure_years_by_age.len());
+    if deaths_by_age.iter().sum::<u32>() == 0 {
+        return None;
+    }
+    let mut survivors = 1.0;
+    let mut years_lived = 0.0;
+    for age in 0..ages {
+        let deaths = deaths_by_age.get(age).copied().unwrap_or(0) as f64;
+        let exposure = exposure_years_by_age.get(age).copied().unwrap_or(0.0);
+        let probability_of_dying = if age == ages - 1 {
+            1.0
+        } else if exposure > 0.0 {
+            let rate = deaths / exposure;
+            (rate / (1.0 + 0.5 * rate)).min(1.0)
+        } else if deaths > 0.0 {
+            1.0
+        } else {
+            0.0
+        };
+        years_lived += survivors * (1.0 - 0.5 * probability_of_dying);
+        survivors *= 1.0 - probability_of_dying;
+    }
+    Some(years_lived)
+}
+
+#[measured]
+pub fn mortality_window(
+    mut egui_context: EguiContexts,
+    stats: Res<Statistics>,
+    config: Res<Config>,
+) {
+    let year_length = config.game.year_length.value.max(1);
+    egui::Window::new("Mortality").show(egui_context.ctx_mut(), |ui| {
+        if let Some(expectancy) = stats
+            .mortality_history
+            .last()
+            .and_then(|year| year.life_expectancy(year_length))
+        {
+            ui.label(format!(
+                "Life expectancy at birth in last year: {:.1} years",
+                expectancy
+            ));
+        }
+        draw_mortality_table(ui, &stats, year_length);
+        plot_deaths_by_cause(ui, &stats);
+    });
+}
+
+fn draw_mortality_table(ui: &mut Ui, stats: &Statistics, year_length: u32) {
+    egui::Grid::new("mortality_table")
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("Year");
+            for cause in CauseOfDeath::ALL.iter() {
+                ui.label(cause.name());
+            }
+            ui.label("Total");
+            ui.label("Life expectancy");
+            ui.end_row();
+            let years = stats.mortality_history.len();
+            for (year, mortality) in stats
+                .mortality_history
+                .iter()
+                .enumerate()
+                .skip(years.saturating_sub(YEARS_IN_TABLE))
+                .rev()
+            {
+                ui.label(format!("{}", year));
+                for deaths in mortality.deaths.iter() {
+                    ui.label(format!("{}", deaths));
+                }
+                ui.label(format!("{}", mortality.total_deaths()));
+                match mortality.life_expectancy(year_length) {
+                    Some(expectancy) => ui.label(format!("{:.1}", expectancy)),
+                    None => ui.label("-"),
+                };
+                ui.end_row();
+            }
+        });
+}
+
+fn plot_deaths_by_cause(ui: &mut Ui, stats: &Statistics) {
+    Plot::new("deaths_by_cause")
+        .view_aspect(2.0)
+        .legend(Legend {
+            position: Corner::LeftTop,
+            ..default()
+        })
+        .show(ui, |plot_ui| {
+            let years = stats.mortality_history.len();
+            let start = years.saturating_sub(YEARS_IN_PLOT);
+            let mut charts: Vec<BarChart> = Vec::new();
+            for cause in CauseOfDeath::ALL.iter() {
+                let bars = stats.mortality_history[start..]
+                    .iter()
+                    .enumerate()
+                    .map(|(index, year)| {
+                        Bar::new((start + index) as f64, year.deaths[cause.index()] as f64)
+                    })
+                    .collect();
+                let chart = BarChart::new(bars)
+                    .name(cause.name())
+                    .stack_on(&charts.iter().collect::<Vec<_>>());
+                charts.push(chart);
+            }
+            for chart in charts {
+                plot_ui.bar_chart(chart);
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn everyone_dying_in_first_year_should_live_half_a_year() {
+        assert_eq!(life_expectancy(&[10], &[5.0]), Some(0.5));
+    }
+
+    #[test]
+    fn nobody_should_survive_the_oldest_observed_age() {
+        let expectancy = life_expectancy(&[0, 0, 3], &[3.0, 3.0, 1.5]).unwrap();
+        assert!((expectancy - 2.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn higher_mortality_should_lower_life_expectancy() {
+        let exposure = [10.0, 10.0, 10.0, 10.0];
+        let low = life_expectancy(&[1, 1, 1, 5], &exposure).unwrap();
+        let high = life_expectancy(&[5, 5, 5, 5], &exposure).unwrap();
+        assert!(high < low);
+    }
+
+    #[test]
+    fn no_deaths_should_give_no_estimate() {
+        assert_eq!(life_expectancy(&[0, 0], &[1.0, 1.0]), None);
+    }
+}
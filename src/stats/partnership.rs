@@ -0,0 +1,100 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use macros::measured;
+
+use crate::debug::components::Performance;
+use crate::logic::components::Person;
+use crate::logic::partnership::Partners;
+use crate::stats::economy::{PartnerMatch, Statistics};
+
+#[measured]
+pub fn partnership_window(
+    mut egui_context: EguiContexts,
+    stats: Res<Statistics>,
+    partners: Query<&Partners, With<Person>>,
+) {
+    egui::Window::new("Partnerships").show(egui_context.ctx_mut(), |ui| {
+        // every partnership is stored on both partners
+        let active = partners.iter().map(|p| p.0.len()).sum::<usize>() / 2;
+        let single = partners.iter().filter(|p| p.0.is_empty()).count();
+        ui.label(format!("Active partnerships: {}", active));
+        ui.label(format!("Single people: {}", single));
+        ui.label(format!(
+            "Partnerships formed: {}",
+            stats.partner_matches.len()
+        ));
+        let durations = &stats.partnership_durations;
+        if !durations.is_empty() {
+            ui.label(format!(
+                "Average duration of ended partnerships: {:.0} ticks",
+                durations.iter().map(|d| *d as f64).sum::<f64>() / durations.len() as f64
+            ));
+        }
+        ui.separator();
+        ui.label("Assortative mating (correlation between partners)");
+        let matches = &stats.partner_matches;
+        ui.label(format!(
+            "Wealth: {}",
+            format_correlation(matches, |m| (m.male_wealth, m.female_wealth))
+        ));
+        ui.label(format!(
+            "Age: {}",
+            format_correlation(matches, |m| (m.male_age, m.female_age))
+        ));
+    });
+}
+
+fn format_correlation(
+    matches: &[PartnerMatch],
+    values: impl Fn(&PartnerMatch) -> (f32, f32),
+) -> String {
+    let (male, female): (Vec<f64>, Vec<f64>) = matches
+        .iter()
+        .map(|m| {
+            let (a, b) = values(m);
+            (a as f64, b as f64)
+        })
+        .unzip();
+    match pearson_correlation(&male, &female) {
+        Some(correlation) => format!("{:.2}", correlation),
+        None => "not enough data".to_string(),
+    }
+}
+
+/// Returns `None` when there are less than two samples or one of the series is constant
+fn pearson_correlation(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    let n = xs.len().min(ys.len());
+    if n < 2 {
+        return None;
+    }
+    let mean_x = xs[..n].iter().sum::<f64>() / n as f64;
+    let mean_y = ys[..n].iter().sum::<f64>() / n as f64;
+    let mut covariance = 0.0;
+    let mut variance_x = 0.0;
+    let mut variance_y = 0.0;
+    for (x, y) in xs.iter().zip(ys.iter()) {
+        covariance += (x - mean_x) * (y - mean_y);
+        variance_x += (x - mean_x) * (x - mean_x);
+        variance_y += (y - mean_y) * (y - mean_y);
+    }
+    if variance_x == 0.0 || variance_y == 0.0 {
+        return None;
+    }
+    Some(covariance / (variance_x * variance_y).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn correlation_of_linear_series() {
+        let xs = [1.0, 2.0, 3.0, 4.0];
+        let ys = [2.0, 4.0, 6.0, 8.0];
+        let reversed = [8.0, 6.0, 4.0, 2.0];
+        assert!((pearson_correlation(&xs, &ys).unwrap() - 1.0).abs() < 1e-9);
+        assert!((pearson_correlation(&xs, &reversed).unwrap() + 1.0).abs() < 1e-9);
+        assert_eq!(pearson_correlation(&xs, &[1.0, 1.0, 1.0, 1.0]), None);
+        assert_eq!(pearson_correlation(&[1.0], &[1.0]), None);
+    }
+}
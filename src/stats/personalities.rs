@@ -0,0 +1,195 @@
+use bevy::prelude::*;
+use bevy_egui::egui::plot::{Corner, Legend, Plot};
+use bevy_egui::{egui, EguiContexts};
+use macros::measured;
+
+use crate::config::Config;
+use crate::debug::components::Performance;
+use crate::logic::components::{Dead, FoodAmount, Person};
+use crate::logic::lineage::Parents;
+use crate::logic::personality::{Personalities, Personality};
+use crate::rendering::ui::{create_plot_line_f64, draw_bool_config_value};
+use crate::stats::economy::Statistics;
+use crate::stats::ui::get_range;
+
+/// Outcome of one personality profile, counts of members, deaths and births are since the start
+#[derive(Clone, Copy, Default)]
+pub struct ProfileSummary {
+    pub alive: u32,
+    /// Food carried by an average living member
+    pub wealth: f64,
+    pub members: u32,
+    pub deaths: u32,
+    /// Children born to mothers of this profile
+    pub births: u32,
+}
+
+impl ProfileSummary {
+    /// Part of all members that is still alive
+    pub fn survival(&self) -> f64 {
+        if self.members > 0 {
+            self.alive as f64 / self.members as f64
+        } else {
+            0.0
+        }
+    }
+
+    pub fn children_per_member(&self) -> f64 {
+        if self.members > 0 {
+            self.births as f64 / self.members as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Position of a profile among the loaded profiles, which is where its summary is kept
+fn profile_index(personalities: &Personalities, profile: &str) -> Option<usize> {
+    personalities
+        .profiles
+        .keys()
+        .position(|name| name == profile)
+}
+
+#[allow(clippy::too_many_arguments)]
+#[measured]
+pub fn personality_statistics(
+    joined: Query<&Personality, Added<Personality>>,
+    died: Query<&Personality, Added<Dead>>,
+    born: Query<&Parents, Added<Person>>,
+    personalities: Query<&Personality>,
+    living: Query<(&Personality, &FoodAmount), With<Person>>,
+    profiles: Res<Personalities>,
+    mut stats: ResMut<Statistics>,
+) {
+    let mut summaries = stats
+        .personality_history
+        .last()
+        .cloned()
+        .unwrap_or_default();
+    summaries.resize(profiles.profiles.len(), ProfileSummary::default());
+    for summary in summaries.iter_mut() {
+        summary.alive = 0;
+        summary.wealth = 0.0;
+    }
+    for personality in joined.iter() {
+        if let Some(index) = profile_index(&profiles, &personality.profile) {
+            summaries[index].members += 1;
+        }
+    }
+    for personality in died.iter() {
+        if let Some(index) = profile_index(&profiles, &personality.profile) {
+            summaries[index].deaths += 1;
+        }
+    }
+    for parents in born.iter() {
+        if let Some(index) = personalities
+            .get(parents.mother)
+            .ok()
+            .and_then(|mother| profile_index(&profiles, &mother.profile))
+        {
+            summaries[index].births += 1;
+        }
+    }
+    for (personality, food) in living.iter() {
+        if let Some(index) = profile_index(&profiles, &personality.profile) {
+            summaries[index].alive += 1;
+            summaries[index].wealth += (food.apples + food.oranges) as f64;
+        }
+    }
+    for summary in summaries.iter_mut() {
+        if summary.alive > 0 {
+            summary.wealth /= summary.alive as f64;
+        }
+    }
+    // nothing is recorded until people get personalities
+    if !joined.is_empty() || !stats.personality_history.is_empty() {
+        stats.personality_history.push(summaries);
+    }
+}
+
+#[measured]
+pub fn personalities_window(
+    mut egui_context: EguiContexts,
+    personalities: Res<Personalities>,
+    stats: Res<Statistics>,
+    mut config: ResMut<Config>,
+) {
+    egui::Window::new("Personalities").show(egui_context.ctx_mut(), |ui| {
+        draw_bool_config_value(ui, &mut config.ai.personalities);
+        let current = stats.personality_history.last();
+        egui::Grid::new("personalities_table")
+            .striped(true)
+            .show(ui, |ui| {
+                for header in [
+                    "Profile",
+                    "Share",
+                    "Thinker",
+                    "Alive",
+                    "Died",
+                    "Survival",
+                    "Wealth",
+                    "Children",
+                    "Children per member",
+                ] {
+                    ui.label(header);
+                }
+                ui.end_row();
+                for (index, (name, profile)) in personalities.profiles.iter().enumerate() {
+                    let summary = current
+                        .and_then(|summaries| summaries.get(index))
+                        .copied()
+                        .unwrap_or_default();
+                    ui.label(name).on_hover_text(&profile.description);
+                    ui.label(format!("{:.2}", profile.share));
+                    ui.label(&profile.thinker);
+                    ui.label(format!("{}", summary.alive));
+                    ui.label(format!("{}", summary.deaths));
+                    ui.label(format!("{:.0}%", summary.survival() * 100.0));
+                    ui.label(format!("{:.1}", summary.wealth));
+                    ui.label(format!("{}", summary.births));
+                    ui.label(format!("{:.2}", summary.children_per_member()));
+                    ui.end_row();
+                }
+            });
+        let history = get_range(&stats.personality_history, config.ui.plot_time_range.value);
+        let lines = |value: fn(&ProfileSummary) -> f64| {
+            personalities
+                .profiles
+                .keys()
+                .enumerate()
+                .map(|(index, name)| {
+                    let values = history
+                        .iter()
+                        .map(|summaries| summaries.get(index).map(value).unwrap_or(0.0))
+                        .collect::<Vec<_>>();
+                    create_plot_line_f64(name, &values)
+                })
+                .collect::<Vec<_>>()
+        };
+        ui.label("Living members");
+        Plot::new("personalities_alive")
+            .view_aspect(2.0)
+            .legend(Legend {
+                position: Corner::LeftTop,
+                ..default()
+            })
+            .show(ui, |plot_ui| {
+                for line in lines(|s| s.alive as f64) {
+                    plot_ui.line(line);
+                }
+            });
+        ui.label("Wealth of an average member");
+        Plot::new("personalities_wealth")
+            .view_aspect(2.0)
+            .legend(Legend {
+                position: Corner::LeftTop,
+                ..default()
+            })
+            .show(ui, |plot_ui| {
+                for line in lines(|s| s.wealth) {
+                    plot_ui.line(line);
+                }
+            });
+    });
+}
@@ -0,0 +1,80 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use macros::measured;
+
+use crate::debug::components::Performance;
+use crate::input::selection::SelectedPerson;
+use crate::logic::components::Name;
+use crate::logic::relationships::{export_relationships, Relationships};
+
+const RELATIONSHIPS_EXPORT_PATH: &str = "./relationships.csv";
+const STRONG_TIE_TRUST: f32 = 0.5;
+const CLOSEST_PEOPLE_SHOWN: usize = 5;
+
+#[measured]
+pub fn relationships_window(
+    mut egui_context: EguiContexts,
+    relationships: Res<Relationships>,
+    selected: Res<SelectedPerson>,
+    people: Query<(Entity, &Name)>,
+) {
+    egui::Window::new("Relationships").show(egui_context.ctx_mut(), |ui| {
+        let pairs = &relationships.pairs;
+        ui.label(format!("Relationships: {}", pairs.len()));
+        ui.label(format!(
+            "Strong ties (trust above {}): {}",
+            STRONG_TIE_TRUST,
+            pairs
+                .values()
+                .filter(|r| r.trust > STRONG_TIE_TRUST)
+                .count()
+        ));
+        if !pairs.is_empty() {
+            ui.label(format!(
+                "Average trust: {:.3}",
+                pairs.values().map(|r| r.trust).sum::<f32>() / pairs.len() as f32
+            ));
+        }
+        if let Some(person) = selected.0 {
+            let mut closest = pairs
+                .iter()
+                .filter_map(|((a, b), relationship)| {
+                    if *a == person {
+                        Some((*b, relationship))
+                    } else if *b == person {
+                        Some((*a, relationship))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+            closest.sort_by(|(_, x), (_, y)| y.trust.total_cmp(&x.trust));
+            ui.separator();
+            ui.label("Closest people of the selected person");
+            egui::Grid::new("closest_people")
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("Name");
+                    ui.label("Meetings");
+                    ui.label("Trades");
+                    ui.label("Trust");
+                    ui.end_row();
+                    for (other, relationship) in closest.iter().take(CLOSEST_PEOPLE_SHOWN) {
+                        let name = people
+                            .get(*other)
+                            .map(|(_, name)| name.0.clone())
+                            .unwrap_or_else(|_| format!("{}", other.index()));
+                        ui.label(name);
+                        ui.label(format!("{}", relationship.interactions));
+                        ui.label(format!("{}", relationship.trades));
+                        ui.label(format!("{:.2}", relationship.trust));
+                        ui.end_row();
+                    }
+                });
+        }
+        ui.separator();
+        if ui.button("Export to CSV").clicked() {
+            export_relationships(&relationships, &people, RELATIONSHIPS_EXPORT_PATH);
+        }
+    });
+}
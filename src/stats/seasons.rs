@@ -0,0 +1,136 @@
+use bevy::prelude::*;
+use bevy_egui::egui::plot::{Corner, Legend, Plot};
+use bevy_egui::{egui, EguiContexts};
+use macros::measured;
+
+use crate::config::Config;
+use crate::debug::components::Performance;
+use crate::logic::components::{Dead, FoodAmount, Person};
+use crate::logic::health::Health;
+use crate::logic::people::Migrant;
+use crate::logic::planet::{freshest_growing_row, TotalTicks};
+use crate::rendering::ui::{create_plot_line_f64, draw_config_value};
+use crate::stats::economy::Statistics;
+use crate::stats::ui::get_range;
+
+/// Living people of one group with their average food and health, deaths are since the start
+#[derive(Clone, Copy, Default)]
+pub struct GroupSummary {
+    pub alive: u32,
+    pub deaths: u32,
+    pub wealth: f64,
+    pub health: f64,
+}
+
+impl GroupSummary {
+    fn add(&mut self, food: &FoodAmount, health: Option<&Health>) {
+        self.alive += 1;
+        self.wealth += (food.apples + food.oranges) as f64;
+        self.health += health.map_or(1.0, |h| h.0) as f64;
+    }
+
+    fn average(&mut self) {
+        if self.alive > 0 {
+            self.wealth /= self.alive as f64;
+            self.health /= self.alive as f64;
+        }
+    }
+}
+
+/// People who followed the growing season compared with those who stayed where they were
+#[derive(Clone, Copy, Default)]
+pub struct MigrationSummary {
+    pub migrants: GroupSummary,
+    pub stationary: GroupSummary,
+}
+
+#[measured]
+#[allow(clippy::type_complexity)]
+pub fn migration_statistics(
+    died: Query<Option<&Migrant>, Added<Dead>>,
+    living: Query<(&FoodAmount, Option<&Health>, Option<&Migrant>), With<Person>>,
+    mut stats: ResMut<Statistics>,
+) {
+    let mut summary = MigrationSummary::default();
+    if let Some(last) = stats.migration_history.last() {
+        summary.migrants.deaths = last.migrants.deaths;
+        summary.stationary.deaths = last.stationary.deaths;
+    }
+    for migrant in died.iter() {
+        if migrant.is_some() {
+            summary.migrants.deaths += 1;
+        } else {
+            summary.stationary.deaths += 1;
+        }
+    }
+    for (food, health, migrant) in living.iter() {
+        if migrant.is_some() {
+            summary.migrants.add(food, health);
+        } else {
+            summary.stationary.add(food, health);
+        }
+    }
+    summary.migrants.average();
+    summary.stationary.average();
+    stats.migration_history.push(summary);
+}
+
+#[measured]
+pub fn seasons_window(
+    mut egui_context: EguiContexts,
+    stats: Res<Statistics>,
+    time: Res<TotalTicks>,
+    mut config: ResMut<Config>,
+) {
+    egui::Window::new("Seasons").show(egui_context.ctx_mut(), |ui| {
+        draw_config_value(ui, &mut config.ai.season_horizon);
+        draw_config_value(ui, &mut config.ai.stockpile_factor);
+        if let Some(row) = freshest_growing_row(
+            time.0,
+            config.map.size_y.value,
+            config.game.year_length.value,
+            config.game.growing_season_length.value,
+        ) {
+            ui.label(format!("Growing season started last in row {}", row));
+        }
+        let current = stats.migration_history.last().copied().unwrap_or_default();
+        egui::Grid::new("seasons_table")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Group");
+                ui.label("Alive");
+                ui.label("Died");
+                ui.label("Wealth");
+                ui.label("Health");
+                ui.end_row();
+                for (name, group) in [
+                    ("Migrants", current.migrants),
+                    ("Stationary", current.stationary),
+                ] {
+                    ui.label(name);
+                    ui.label(format!("{}", group.alive));
+                    ui.label(format!("{}", group.deaths));
+                    ui.label(format!("{:.1}", group.wealth));
+                    ui.label(format!("{:.2}", group.health));
+                    ui.end_row();
+                }
+            });
+        Plot::new("seasons")
+            .view_aspect(2.0)
+            .legend(Legend {
+                position: Corner::LeftTop,
+                ..default()
+            })
+            .show(ui, |plot_ui| {
+                let history = get_range(&stats.migration_history, config.ui.plot_time_range.value);
+                let line = |name, value: fn(&MigrationSummary) -> f64| {
+                    let values = history.iter().map(value).collect::<Vec<_>>();
+                    create_plot_line_f64(name, &values)
+                };
+                plot_ui.line(line("Migrants", |s| s.migrants.alive as f64));
+                plot_ui.line(line("Stationary", |s| s.stationary.alive as f64));
+                plot_ui.line(line("Wealth of migrants", |s| s.migrants.wealth));
+                plot_ui.line(line("Wealth of stationary", |s| s.stationary.wealth));
+            });
+    });
+}
@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+use bevy_egui::egui::plot::{Corner, Legend, Plot};
+use bevy_egui::{egui, EguiContexts};
+use macros::measured;
+
+use crate::config::Config;
+use crate::debug::components::Performance;
+use crate::logic::planet::TotalTicks;
+use crate::logic::tribes::{Territory, Tribes};
+use crate::rendering::ui::{create_plot_line_f64, draw_bool_config_value};
+use crate::stats::economy::Statistics;
+use crate::stats::ui::get_range;
+
+#[derive(Clone, Copy, Default)]
+pub struct TribeSummary {
+    pub tribes: u32,
+    pub members: u32,
+    pub largest: u32,
+    pub territory: u32,
+}
+
+#[measured]
+pub fn tribes_statistics(
+    tribes: Res<Tribes>,
+    territory: Res<Territory>,
+    mut stats: ResMut<Statistics>,
+) {
+    let summary = TribeSummary {
+        tribes: tribes.tribes.len() as u32,
+        members: tribes.tribes.values().map(|tribe| tribe.members).sum(),
+        largest: tribes
+            .tribes
+            .values()
+            .map(|tribe| tribe.members)
+            .max()
+            .unwrap_or(0),
+        territory: territory.claims.len() as u32,
+    };
+    stats.tribes_history.push(summary);
+}
+
+#[measured]
+pub fn tribes_window(
+    mut egui_context: EguiContexts,
+    tribes: Res<Tribes>,
+    territory: Res<Territory>,
+    stats: Res<Statistics>,
+    time: Res<TotalTicks>,
+    mut config: ResMut<Config>,
+) {
+    egui::Window::new("Tribes").show(egui_context.ctx_mut(), |ui| {
+        draw_bool_config_value(ui, &mut config.ui.show_territories);
+        ui.label(format!("Tribes: {}", tribes.tribes.len()));
+        let areas = territory.areas();
+        let mut sorted = tribes.tribes.iter().collect::<Vec<_>>();
+        sorted.sort_by_key(|(_, tribe)| std::cmp::Reverse(tribe.members));
+        egui::Grid::new("tribes_table")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Tribe");
+                ui.label("Members");
+                ui.label("Territory");
+                ui.label("Age");
+                ui.end_row();
+                for (id, tribe) in sorted {
+                    ui.label(&tribe.name);
+                    ui.label(format!("{}", tribe.members));
+                    ui.label(format!("{}", areas.get(id).copied().unwrap_or(0)));
+                    ui.label(format!("{}", time.0 - tribe.founded));
+                    ui.end_row();
+                }
+            });
+        Plot::new("tribes")
+            .view_aspect(2.0)
+            .legend(Legend {
+                position: Corner::LeftTop,
+                ..default()
+            })
+            .show(ui, |plot_ui| {
+                let history = get_range(&stats.tribes_history, config.ui.plot_time_range.value);
+                let line = |name, value: fn(&TribeSummary) -> u32| {
+                    let values = history.iter().map(|s| value(s) as f64).collect::<Vec<_>>();
+                    create_plot_line_f64(name, &values)
+                };
+                plot_ui.line(line("Tribes", |s| s.tribes));
+                plot_ui.line(line("Members", |s| s.members));
+                plot_ui.line(line("Largest tribe", |s| s.largest));
+                plot_ui.line(line("Claimed tiles", |s| s.territory));
+            });
+    });
+}
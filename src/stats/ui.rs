@@ -1,11 +1,16 @@
 use crate::config::Config;
 use crate::debug::components::Performance;
+use crate::logic::abilities::LifeStage;
+use crate::logic::childhood::Orphan;
 use crate::logic::components::{Age, Dead, FoodAmount, FoodSource, FoodType, Person};
-use crate::logic::people::{Female, Fertile, Male};
+use crate::logic::knowledge::Knowledge;
+use crate::logic::people::{Child, Female, Fertile, Male, Old};
+use crate::logic::pregnancy::Pregnant;
 use crate::rendering::ui::{
     add_options_grid, create_histogram, create_plot_line, create_plot_line_f64, draw_config_value,
 };
 use crate::stats::components::Transaction;
+use crate::stats::disease::SirCounts;
 use bevy::prelude::*;
 use bevy_egui::egui::plot::{Corner, Legend, Plot, PlotPoints, Points};
 use bevy_egui::egui::{Color32, Ui};
@@ -14,7 +19,7 @@ use macros::measured;
 
 use crate::stats::economy::Statistics;
 
-#[allow(clippy::too_many_arguments)]
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn stats_window(
     mut egui_context: EguiContexts,
     stats: Res<Statistics>,
@@ -23,9 +28,11 @@ pub fn stats_window(
     females_fertile: Query<&Female, With<Fertile>>,
     males_infertile: Query<&Male, Without<Fertile>>,
     females_infertile: Query<&Female, Without<Fertile>>,
-    people_wealth: Query<&FoodAmount, With<Person>>,
+    people_wealth: Query<(&FoodAmount, Option<&Child>, Option<&Old>), With<Person>>,
     food_sources: Query<(&FoodSource, &FoodAmount)>,
     people: Query<(&Person, &Age)>,
+    pregnant: Query<&Pregnant, With<Person>>,
+    orphans: Query<&Orphan, With<Person>>,
     config: Res<Config>,
 ) {
     egui::Window::new("Stats").show(egui_context.ctx_mut(), |ui| {
@@ -43,6 +50,66 @@ pub fn stats_window(
             females,
             females_fertile.iter().count()
         ));
+        ui.label(format!("Pregnant: {}", pregnant.iter().count()));
+        if let Some(health) = stats.health_history.last() {
+            ui.label(format!(
+                "Average health: {:.2}, weak: {}, starving: {}",
+                health.average, health.weak, health.starving
+            ));
+        }
+        if let Some(knowledge) = stats.knowledge_history.last() {
+            ui.label(format!(
+                "Known food sources per person (largest): {:.1} ({})",
+                knowledge.average, knowledge.largest
+            ));
+            ui.label(format!(
+                "Average age of memories: {:.0}",
+                knowledge.average_age
+            ));
+            if knowledge.price_memories > 0 {
+                ui.label(format!(
+                    "Remembered price: {:.2} oranges per apple by {} people",
+                    knowledge.remembered_price, knowledge.price_memories
+                ));
+            }
+        }
+        if let Some(sir) = stats.sir_history.last() {
+            ui.label(format!(
+                "Sick (exposed): {} ({})",
+                sir.infectious, sir.exposed
+            ));
+            ui.label(format!("Immune: {}", sir.recovered));
+        }
+        let births_in_last_year = get_range(
+            &stats.births_history,
+            config.game.year_length.value as usize,
+        )
+        .iter()
+        .sum::<u32>();
+        ui.label(format!("Births in last year: {}", births_in_last_year));
+        let child_deaths_in_last_year = get_range(
+            &stats.child_deaths_history,
+            config.game.year_length.value as usize,
+        )
+        .iter()
+        .sum::<u32>();
+        let per_thousand_births = if births_in_last_year > 0 {
+            format!(
+                "{:.0}",
+                child_deaths_in_last_year as f32 / births_in_last_year as f32 * 1000.0
+            )
+        } else {
+            String::from("-")
+        };
+        ui.label(format!(
+            "Child deaths in last year (per 1000 births): {} ({})",
+            child_deaths_in_last_year, per_thousand_births
+        ));
+        ui.label(format!(
+            "Orphans (died as orphans): {} ({})",
+            orphans.iter().count(),
+            stats.orphan_deaths
+        ));
         ui.label(format!("Food: {}", stats.current_food));
         ui.label(format!("Apples: {}", stats.current_apples));
         ui.label(format!("Oranges: {}", stats.current_oranges));
@@ -54,13 +121,11 @@ pub fn stats_window(
                 FoodType::Apple => {
                     if food_amount.apples < 3 {
                         growing_apple_trees += 1
-                    } else {
                     }
                 }
                 FoodType::Orange => {
                     if food_amount.oranges < 3 {
                         growing_orange_trees += 1
-                    } else {
                     }
                 }
             });
@@ -121,10 +186,26 @@ pub fn stats_window(
             calculate_gini_coefficient(
                 &people_wealth
                     .iter()
-                    .map(|f| f.apples as f64 + f.oranges as f64)
+                    .map(|(f, _, _)| f.apples as f64 + f.oranges as f64)
                     .collect::<Vec<_>>()
             )
         ));
+        let mut wealth_by_stage = [(0.0, 0); 3];
+        for (food, child, old) in people_wealth.iter() {
+            let index = match LifeStage::of(child, old) {
+                LifeStage::Child => 0,
+                LifeStage::Adult => 1,
+                LifeStage::Old => 2,
+            };
+            wealth_by_stage[index].0 += (food.apples + food.oranges) as f64;
+            wealth_by_stage[index].1 += 1;
+        }
+        let [child, adult, old] =
+            wealth_by_stage.map(|(food, people)| food / (people as f64).max(1.0));
+        ui.label(format!(
+            "Average food of children / adults / the old: {:.1} / {:.1} / {:.1}",
+            child, adult, old
+        ));
     });
 }
 
@@ -156,6 +237,7 @@ pub fn food_statistics(
     stats: Res<Statistics>,
     mut config: ResMut<Config>,
     query: Query<(&Person, &Age), Without<Dead>>,
+    knowledge: Query<&Knowledge, With<Person>>,
 ) {
     egui::Window::new("Plots").show(egui_context.ctx_mut(), |ui| {
         ui.label("Foods and people over time");
@@ -166,6 +248,10 @@ pub fn food_statistics(
         plot_food_on_planet(&stats, &mut config, ui);
         plot_food_for_people(&stats, &mut config, ui);
         plot_people(&stats, &mut config, ui);
+        plot_birth_rate(&stats, &mut config, ui);
+        plot_epidemic(&stats, &mut config, ui);
+        plot_health(&stats, &mut config, ui);
+        plot_knowledge(&stats, &mut config, knowledge, ui);
         plot_ages(&mut config, query, ui);
     });
 }
@@ -245,6 +331,141 @@ fn plot_people(stats: &Res<Statistics>, config: &mut ResMut<Config>, ui: &mut Ui
         });
 }
 
+fn plot_birth_rate(stats: &Res<Statistics>, config: &mut ResMut<Config>, ui: &mut Ui) {
+    Plot::new("birth_rate")
+        .view_aspect(2.0)
+        .legend(Legend {
+            position: Corner::LeftTop,
+            ..default()
+        })
+        .show(ui, |plot_ui| {
+            let range = config.ui.plot_time_range.value;
+            let year_length = config.game.year_length.value as usize;
+            let people = moving_average(
+                &to_f64(get_range(&stats.people_history, range)),
+                year_length,
+            );
+            let per_thousand_people = |events: &Vec<u32>| {
+                moving_average(&to_f64(get_range(events, range)), year_length)
+                    .iter()
+                    .zip(people.iter())
+                    // nobody is born after everybody died, the rate stays at zero then
+                    .map(|(events, people)| events * year_length as f64 / people.max(1.0) * 1000.0)
+                    .collect::<Vec<_>>()
+            };
+            plot_ui.line(create_plot_line_f64(
+                "Births per 1000 people per year",
+                &per_thousand_people(&stats.births_history),
+            ));
+            plot_ui.line(create_plot_line_f64(
+                "Conceptions per 1000 people per year",
+                &per_thousand_people(&stats.conceptions_history),
+            ));
+            plot_ui.line(create_plot_line_f64(
+                "Child deaths per 1000 people per year",
+                &per_thousand_people(&stats.child_deaths_history),
+            ));
+        });
+}
+
+fn plot_epidemic(stats: &Res<Statistics>, config: &mut ResMut<Config>, ui: &mut Ui) {
+    Plot::new("epidemic")
+        .view_aspect(2.0)
+        .legend(Legend {
+            position: Corner::LeftTop,
+            ..default()
+        })
+        .show(ui, |plot_ui| {
+            let range = config.ui.plot_time_range.value;
+            let sir = get_range(&stats.sir_history, range);
+            let compartment = |value: fn(&SirCounts) -> u32| {
+                sir.iter()
+                    .map(|counts| value(counts) as f64)
+                    .collect::<Vec<_>>()
+            };
+            plot_ui.line(create_plot_line_f64(
+                "Susceptible",
+                &compartment(|c| c.susceptible),
+            ));
+            plot_ui.line(create_plot_line_f64("Exposed", &compartment(|c| c.exposed)));
+            plot_ui.line(
+                create_plot_line_f64("Infectious", &compartment(|c| c.infectious))
+                    .color(Color32::RED),
+            );
+            plot_ui.line(create_plot_line_f64(
+                "Recovered",
+                &compartment(|c| c.recovered),
+            ));
+            plot_ui.line(create_plot_line_f64(
+                "New infections (yearly average)",
+                &moving_average(
+                    &to_f64(get_range(&stats.infections_history, range)),
+                    config.game.year_length.value as usize,
+                ),
+            ));
+        });
+}
+
+fn plot_health(stats: &Res<Statistics>, config: &mut ResMut<Config>, ui: &mut Ui) {
+    Plot::new("health")
+        .view_aspect(2.0)
+        .legend(Legend {
+            position: Corner::LeftTop,
+            ..default()
+        })
+        .show(ui, |plot_ui| {
+            let health = get_range(&stats.health_history, config.ui.plot_time_range.value);
+            let weak = health.iter().map(|h| h.weak as f64).collect::<Vec<_>>();
+            let starving = health.iter().map(|h| h.starving as f64).collect::<Vec<_>>();
+            plot_ui.line(create_plot_line_f64("Weak people", &weak));
+            plot_ui.line(create_plot_line_f64("Starving people", &starving).color(Color32::RED));
+        });
+}
+
+fn plot_knowledge(
+    stats: &Res<Statistics>,
+    config: &mut ResMut<Config>,
+    knowledge: Query<&Knowledge, With<Person>>,
+    ui: &mut Ui,
+) {
+    Plot::new("knowledge")
+        .view_aspect(2.0)
+        .legend(Legend {
+            position: Corner::LeftTop,
+            ..default()
+        })
+        .show(ui, |plot_ui| {
+            let average = get_range(&stats.knowledge_history, config.ui.plot_time_range.value)
+                .iter()
+                .map(|k| k.average)
+                .collect::<Vec<_>>();
+            let gossip = get_range(&stats.gossip_history, config.ui.plot_time_range.value);
+            plot_ui.line(create_plot_line_f64("Known food sources", &average));
+            plot_ui.line(create_plot_line_f64("Learned from others", &to_f64(gossip)));
+        });
+    Plot::new("knowledge_per_person")
+        .view_aspect(2.0)
+        .legend(Legend {
+            position: Corner::LeftTop,
+            ..default()
+        })
+        .show(ui, |plot_ui| {
+            let sizes = knowledge
+                .iter()
+                .map(|k| k.infos.len() as u32)
+                .collect::<Vec<_>>();
+            plot_ui.bar_chart(create_histogram(
+                "Known food sources per person",
+                &sizes,
+                config.ui.age_histogram_bins.value,
+            ));
+        });
+}
+
+fn to_f64(values: &[u32]) -> Vec<f64> {
+    values.iter().map(|v| *v as f64).collect()
+}
+
 fn plot_ages(
     config: &mut ResMut<Config>,
     query: Query<(&Person, &Age), Without<Dead>>,
@@ -375,7 +596,7 @@ fn moving_average(data: &[f64], window_size: usize) -> Vec<f64> {
     result
 }
 
-fn get_range<T>(vector: &Vec<T>, last_n: usize) -> &[T] {
+pub(crate) fn get_range<T>(vector: &Vec<T>, last_n: usize) -> &[T] {
     let range = if vector.len() > last_n {
         vector.len() - last_n
     } else {